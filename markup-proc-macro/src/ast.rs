@@ -1,3 +1,33 @@
+/// The templates in a `define!`.
+#[derive(Debug)]
+pub struct Define {
+    pub definitions: Vec<Definition>,
+    pub pending: Option<PendingProps>,
+}
+
+/// The first `..name: Type` field in a `define!` whose fields aren't known yet. Its tokens are
+/// passed to the macro which `#[derive(markup::Props)]` defined for `Type`, which passes them back
+/// to `define!` along with the fields of `Type` added to `fields`.
+#[derive(Debug)]
+pub struct PendingProps {
+    pub path: syn::Path,
+    /// The struct in `Type`, which is checked to derive `markup::Props` as the error about the
+    /// missing macro doesn't say so.
+    pub ty: syn::Type,
+    /// The generics of the template, which `ty` may use.
+    pub generics: syn::Generics,
+    pub where_clause: Option<syn::WhereClause>,
+    /// The fields of the `..name: Type` fields before this one.
+    pub fields: Vec<Vec<syn::Ident>>,
+    pub tokens: proc_macro2::TokenStream,
+}
+
+#[derive(Debug)]
+pub enum Definition {
+    Struct(Struct),
+    Enum(Enum),
+}
+
 #[derive(Debug)]
 pub struct Struct {
     pub name: syn::Ident,
@@ -5,16 +35,105 @@ pub struct Struct {
     pub generics: syn::Generics,
     pub where_clause: Option<syn::WhereClause>,
     pub fields: Vec<syn::Field>,
+    pub props: Vec<Props>,
     pub children: Vec<Node>,
     pub size_hint: usize,
+    pub include: Option<String>,
+    pub options: Options,
+}
+
+#[derive(Debug)]
+pub struct Enum {
+    pub name: syn::Ident,
+    pub attributes: Vec<syn::Attribute>,
+    pub generics: syn::Generics,
+    pub where_clause: Option<syn::WhereClause>,
+    pub variants: Vec<Variant>,
+    pub options: Options,
+}
+
+#[derive(Debug)]
+pub struct Variant {
+    pub name: syn::Ident,
+    pub attributes: Vec<syn::Attribute>,
+    pub fields: Vec<syn::Field>,
+    pub props: Vec<Props>,
+    pub children: Vec<Node>,
+    pub size_hint: usize,
+}
+
+/// A field written as `..name: Type { fields }`, whose `fields` are bound in the template, or as
+/// `..name: Type`, whose fields are those of `Type`.
+#[derive(Debug)]
+pub struct Props {
+    pub name: syn::Ident,
+    /// The path of the struct in `Type`, without generic arguments, to destructure it with.
+    pub path: syn::Path,
+    /// The struct in `Type`, without the references to it.
+    pub ty: syn::Type,
+    pub fields: Vec<syn::Ident>,
+    /// Whether `fields` were listed, rather than filled in by `Define` once they're known.
+    pub listed: bool,
+}
+
+/// A struct with `#[derive(markup::Props)]`.
+#[derive(Debug)]
+pub struct DeriveProps {
+    pub name: syn::Ident,
+    pub generics: syn::Generics,
+    pub fields: Vec<syn::Ident>,
+}
+
+#[derive(Debug)]
+pub struct Derive {
+    pub name: syn::Ident,
+    pub generics: syn::Generics,
+    pub fields: Vec<syn::Ident>,
+    pub children: Vec<Node>,
+    pub options: Options,
+}
+
+#[derive(Debug)]
+pub struct Component {
+    pub attributes: Vec<syn::Attribute>,
+    pub visibility: syn::Visibility,
+    pub signature: syn::Signature,
+    pub statements: Vec<syn::Stmt>,
+    pub children: Vec<Node>,
+    pub options: Options,
 }
 
 #[derive(Debug)]
 pub struct Template {
+    pub parameters: Option<Vec<syn::PatType>>,
     pub children: Vec<Node>,
+    pub include: Option<String>,
+}
+
+/// Set with `#[markup(...)]` on a template.
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    pub minify: bool,
+    pub omit_optional_tags: bool,
+    pub xml: bool,
+    pub lints: Lints,
+    pub attribute_prefixes: Vec<String>,
+}
+
+/// Levels of checks set with `allow(...)`, `warn(...)` and `deny(...)`, in the order they were
+/// given.
+#[derive(Debug, Default, Clone)]
+pub struct Lints(pub Vec<(syn::Ident, Level)>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Node {
     Element(Element),
     If(If),
@@ -22,10 +141,23 @@ pub enum Node {
     Expr(syn::Expr),
     Stmt(syn::Stmt),
     Match(Match),
+    // The parts of `@$"..."`, string literals and captured variables.
+    Interpolated(Vec<syn::Expr>),
+    Entity(Entity),
+    WrapIf(WrapIf),
+}
+
+/// A character reference like `&nbsp;` or `&#160;`.
+#[derive(Debug)]
+pub struct Entity {
+    // As written in the template, like `&nbsp;`.
+    pub reference: String,
+    pub value: String,
 }
 
 #[derive(Debug)]
 pub struct Element {
+    pub lints: Lints,
     pub name: syn::Expr,
     pub id: Option<syn::Expr>,
     pub classes: Vec<syn::Expr>,
@@ -35,34 +167,43 @@ pub struct Element {
 }
 
 #[derive(Debug)]
-pub struct If {
-    pub clauses: Vec<IfClause>,
-    pub default: Option<Vec<Node>>,
+pub struct If<T = Node> {
+    pub clauses: Vec<IfClause<T>>,
+    pub default: Option<Vec<T>>,
 }
 
 #[derive(Debug)]
-pub struct IfClause {
+pub struct IfClause<T = Node> {
     pub test: IfClauseTest,
-    pub consequent: Vec<Node>,
+    pub consequent: Vec<T>,
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum IfClauseTest {
     Expr(syn::Expr),
     Let(syn::Pat, syn::Expr),
 }
 
+/// `@wrap_if test element { children }`, which renders `element` around `children` only if
+/// `test` holds.
+#[derive(Debug)]
+pub struct WrapIf {
+    pub test: IfClauseTest,
+    pub element: Element,
+}
+
 #[derive(Debug)]
-pub struct Match {
+pub struct Match<T = Node> {
     pub expr: syn::Expr,
-    pub clauses: Vec<MatchClause>,
+    pub clauses: Vec<MatchClause<T>>,
 }
 
 #[derive(Debug)]
-pub struct MatchClause {
+pub struct MatchClause<T = Node> {
     pub pat: syn::Pat,
     pub guard: Option<syn::Expr>,
-    pub consequent: Vec<Node>,
+    pub consequent: Vec<T>,
 }
 
 #[derive(Debug)]
@@ -73,7 +214,10 @@ pub struct For {
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum Attribute {
     One(syn::Expr, syn::Expr),
     Many(syn::Expr),
+    If(If<Attribute>),
+    Match(Match<Attribute>),
 }
@@ -0,0 +1,728 @@
+//! Checks run on templates before generating code for them.
+
+use crate::ast::{
+    Attribute, Element, If, IfClause, Level, Lints, Match, MatchClause, Node, Options, WrapIf,
+};
+use crate::generate::literal;
+use crate::html::{ELEMENTS, VOID};
+use crate::spec::{
+    ATTRIBUTES, ATTRIBUTE_PREFIXES, CHILDREN, GLOBAL_ATTRIBUTES, INTERACTIVE, PARENTS, PHRASING,
+    PHRASING_ONLY, TRANSPARENT, VALUES,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+use syn::spanned::Spanned;
+
+/// The checks whose level can be set with `allow(...)`, `warn(...)` and `deny(...)`.
+pub const LINTS: &[&str] = &[
+    "attribute_names",
+    "attribute_values",
+    "content_model",
+    "duplicate_ids",
+    "element_names",
+    "id_references",
+    "missing_alt",
+    "missing_button_type",
+    "missing_href",
+    "missing_label",
+    "missing_lang",
+    "skipped_heading_levels",
+];
+
+/// Names which set the level of several checks at once.
+pub const GROUPS: &[(&str, &[&str])] = &[(
+    "a11y",
+    &[
+        "missing_alt",
+        "missing_button_type",
+        "missing_href",
+        "missing_label",
+        "missing_lang",
+        "skipped_heading_levels",
+    ],
+)];
+
+/// Returns the errors and warnings for `children` as tokens to be put in item or statement
+/// position.
+pub fn check(children: &[Node], options: &Options) -> TokenStream {
+    let mut checker = Checker {
+        options,
+        lints: vec![&options.lints],
+        ancestors: Vec::new(),
+        foreign: 0,
+        labels: Vec::new(),
+        heading: None,
+        ids: Vec::new(),
+        dynamic_ids: false,
+        rendered_ids: false,
+        tokens: TokenStream::new(),
+    };
+    labels(children, &mut checker.labels);
+    if !options.xml {
+        checker.ids = checker.ids(children).into_iter().map(|id| id.0).collect();
+    }
+    checker.nodes(children);
+    checker.tokens
+}
+
+struct Checker<'a> {
+    options: &'a Options,
+    lints: Vec<&'a Lints>,
+    // The lowercased names of the elements we're inside, or `None` for dynamic ones.
+    ancestors: Vec<Option<String>>,
+    // How many elements we're inside whose contents aren't HTML, like `svg`.
+    foreign: usize,
+    // The literal `for` attributes of all `label` elements.
+    labels: Vec<String>,
+    // The level of the last heading.
+    heading: Option<u8>,
+    // The literal ids of all elements, whether any element may have an id which isn't known at
+    // compile time, and whether other templates or raw HTML with ids of their own may be rendered.
+    ids: Vec<String>,
+    dynamic_ids: bool,
+    rendered_ids: bool,
+    tokens: TokenStream,
+}
+
+impl<'a> Checker<'a> {
+    fn error(&mut self, span: Span, message: String) {
+        self.tokens
+            .extend(syn::Error::new(span, message).to_compile_error());
+    }
+
+    fn lint(&mut self, lint: &str, default: Level, span: Span, message: String) {
+        let level = self.level(lint, default);
+        self.report(lint, level, span, message);
+    }
+
+    fn report(&mut self, lint: &str, level: Level, span: Span, message: String) {
+        match level {
+            Level::Allow => {}
+            // There's no stable way for procedural macros to emit warnings, so use a deprecated
+            // constant named after the check instead.
+            Level::Warn => {
+                let lint = syn::Ident::new(lint, span);
+                self.tokens.extend(quote_spanned! {
+                    span =>
+                    const _: () = {
+                        #[deprecated(note = #message)]
+                        #[allow(non_upper_case_globals)]
+                        const #lint: () = ();
+                        #lint
+                    };
+                });
+            }
+            Level::Deny => self.error(span, message),
+        }
+    }
+
+    fn level(&self, lint: &str, default: Level) -> Level {
+        self.explicit_level(lint).unwrap_or(default)
+    }
+
+    /// The level `lint` has been set to on the template or the elements we're inside, if any.
+    fn explicit_level(&self, lint: &str) -> Option<Level> {
+        self.lints
+            .iter()
+            .rev()
+            .flat_map(|lints| lints.0.iter().rev())
+            .find(|(name, _)| {
+                name == lint
+                    || GROUPS
+                        .iter()
+                        .any(|(group, lints)| name == group && lints.contains(&lint))
+            })
+            .map(|(_, level)| *level)
+    }
+
+    fn nodes(&mut self, nodes: &'a [Node]) {
+        for node in nodes {
+            match node {
+                Node::Element(element) | Node::WrapIf(WrapIf { element, .. }) => {
+                    self.element(element)
+                }
+                Node::If(If { clauses, default }) => {
+                    for IfClause { consequent, .. } in clauses {
+                        self.nodes(consequent);
+                    }
+                    if let Some(default) = default {
+                        self.nodes(default);
+                    }
+                }
+                Node::Match(Match { clauses, .. }) => {
+                    for MatchClause { consequent, .. } in clauses {
+                        self.nodes(consequent);
+                    }
+                }
+                Node::For(for_) => self.nodes(&for_.body),
+                Node::Expr(_) | Node::Stmt(_) | Node::Interpolated(_) | Node::Entity(_) => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: &'a Element) {
+        self.lints.push(&element.lints);
+        let original = literal(&element.name);
+        let name = original.as_ref().map(|name| name.to_ascii_lowercase());
+        if let (Some(original), Some(name)) = (&original, &name) {
+            // Any element can be self-closing in XML.
+            if !self.options.xml {
+                self.void(original, name, element);
+            }
+            if !self.options.xml && self.foreign == 0 {
+                self.content_model(name, element);
+                self.names(original, name, element);
+                self.values(name, element);
+                self.a11y(name, element);
+            }
+        }
+        // References to ids which a rendered template may have are only reported if asked for.
+        let rendered_ids = self.rendered_ids && self.explicit_level("id_references").is_none();
+        if !self.options.xml && self.foreign == 0 && !self.dynamic_ids && !rendered_ids {
+            self.references(element);
+        }
+        let foreign = matches!(name.as_deref(), Some("math" | "svg" | "template"));
+        self.foreign += foreign as usize;
+        self.ancestors.push(name);
+        self.nodes(&element.children);
+        self.ancestors.pop();
+        self.foreign -= foreign as usize;
+        self.lints.pop();
+    }
+
+    /// Returns the literal ids of the elements in `nodes` and reports the ones used more than once.
+    /// Ids in different branches of an `@if` or `@match` aren't duplicates of each other.
+    fn ids(&mut self, nodes: &'a [Node]) -> Vec<Id> {
+        let mut ids = Vec::new();
+        for node in nodes {
+            let found = match node {
+                Node::Element(element) | Node::WrapIf(WrapIf { element, .. }) => {
+                    self.lints.push(&element.lints);
+                    let mut found = Vec::new();
+                    let id = element.id.as_ref().or_else(|| attribute(element, "id"));
+                    match id.map(|id| (literal(id), id.span())) {
+                        Some((Some(id), span)) => {
+                            let level = self.level("duplicate_ids", Level::Warn);
+                            found.push(Id(id, span, level));
+                        }
+                        Some((None, _)) => self.dynamic_ids = true,
+                        None => self.dynamic_ids |= dynamic_attributes(element),
+                    }
+                    let children = self.ids(&element.children);
+                    self.merge(&mut found, children);
+                    self.lints.pop();
+                    found
+                }
+                Node::If(If { clauses, default }) => {
+                    let mut branches = clauses
+                        .iter()
+                        .map(|IfClause { consequent, .. }| self.ids(consequent))
+                        .collect::<Vec<_>>();
+                    if let Some(default) = default {
+                        branches.push(self.ids(default));
+                    }
+                    union(branches)
+                }
+                Node::Match(Match { clauses, .. }) => union(
+                    clauses
+                        .iter()
+                        .map(|MatchClause { consequent, .. }| self.ids(consequent))
+                        .collect(),
+                ),
+                Node::For(for_) => self.ids(&for_.body),
+                // Other templates and raw HTML rendered here may have ids of their own.
+                Node::Expr(syn::Expr::Lit(_)) | Node::Stmt(_) | Node::Entity(_) => Vec::new(),
+                Node::Expr(_) | Node::Interpolated(_) => {
+                    self.rendered_ids = true;
+                    Vec::new()
+                }
+            };
+            self.merge(&mut ids, found);
+        }
+        ids
+    }
+
+    fn merge(&mut self, ids: &mut Vec<Id>, found: Vec<Id>) {
+        for Id(id, span, level) in found {
+            if ids.iter().any(|other| other.0 == id) {
+                let message = format!("the id `{id}` is already used in this template");
+                self.report("duplicate_ids", level, span, message);
+            } else {
+                ids.push(Id(id, span, level));
+            }
+        }
+    }
+
+    fn references(&mut self, element: &Element) {
+        for (name, value) in pairs(&element.attributes) {
+            let (name, string) = match (literal(name), literal(value)) {
+                (Some(name), Some(string)) => (name.to_ascii_lowercase(), string),
+                _ => continue,
+            };
+            let references = match name.as_str() {
+                "for" => vec![string.trim()],
+                "aria-labelledby" | "aria-describedby" | "aria-controls" => {
+                    string.split_ascii_whitespace().collect()
+                }
+                // `#` and `#top` go to the top of the page.
+                "href" => match string.strip_prefix('#') {
+                    Some(fragment)
+                        if !fragment.is_empty() && !fragment.eq_ignore_ascii_case("top") =>
+                    {
+                        vec![fragment]
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            for reference in references {
+                if self.ids.iter().any(|id| id == reference) {
+                    continue;
+                }
+                let message = match suggest(reference, self.ids.iter().map(String::as_str)) {
+                    Some(suggestion) => format!(
+                        "no element in this template has the id `{reference}`, did you mean `{suggestion}`?"
+                    ),
+                    None => format!("no element in this template has the id `{reference}`"),
+                };
+                self.lint("id_references", Level::Allow, value.span(), message);
+            }
+        }
+    }
+
+    fn void(&mut self, name: &str, lowercase: &str, element: &Element) {
+        let span = element.name.span();
+        if VOID.contains(&lowercase) {
+            if !element.children.is_empty() {
+                self.error(
+                    span,
+                    format!("`{name}` is a void element and can't have children, write `{name};` and put them after it"),
+                );
+            } else if element.close {
+                self.error(
+                    span,
+                    format!("`{name}` is a void element and can't have an end tag, write `{name};` instead of `{name} {{}}`"),
+                );
+            }
+        } else if !element.close && ELEMENTS.contains(&lowercase) {
+            self.error(
+                span,
+                format!("`{name}` isn't a void element and needs an end tag, write `{name} {{}}` instead of `{name};`"),
+            );
+        }
+    }
+
+    fn content_model(&mut self, name: &str, element: &Element) {
+        if !ELEMENTS.contains(&name) {
+            return;
+        }
+        let span = element.name.span();
+        // Transparent elements take the content model of their parent.
+        let parent = self
+            .ancestors
+            .iter()
+            .rev()
+            .find(|ancestor| !matches!(ancestor, Some(ancestor) if TRANSPARENT.contains(&ancestor.as_str())));
+        if let Some(Some(parent)) = parent {
+            let parent = parent.as_str();
+            let children = CHILDREN.iter().find(|(element, _)| *element == parent);
+            let parents = PARENTS.iter().find(|(element, _)| *element == name);
+            if PHRASING_ONLY.contains(&parent) && !PHRASING.contains(&name) {
+                let message = format!("`{parent}` can only contain phrasing content, not `{name}`");
+                return self.lint("content_model", Level::Allow, span, message);
+            }
+            if let Some((_, children)) = children.filter(|(_, children)| !children.contains(&name))
+            {
+                let message = format!(
+                    "`{parent}` can only contain {}, not `{name}`",
+                    list(children, "and")
+                );
+                return self.lint("content_model", Level::Allow, span, message);
+            }
+            if let Some((_, parents)) = parents
+                .filter(|(_, parents)| ELEMENTS.contains(&parent) && !parents.contains(&parent))
+            {
+                let message = format!(
+                    "`{name}` must be inside {}, not `{parent}`",
+                    list(parents, "or")
+                );
+                return self.lint("content_model", Level::Allow, span, message);
+            }
+        }
+        let hidden = element.attributes.iter().any(|attribute| {
+            matches!(attribute, Attribute::One(attribute, value)
+                if literal(attribute).as_deref() == Some("type")
+                    && literal(value).as_deref() == Some("hidden"))
+        });
+        if INTERACTIVE.contains(&name) && !(name == "input" && hidden) {
+            let ancestor = self
+                .ancestors
+                .iter()
+                .rev()
+                .flatten()
+                .find(|ancestor| matches!(ancestor.as_str(), "a" | "button"));
+            if let Some(ancestor) = ancestor {
+                let message =
+                    format!("`{name}` is interactive content and can't be inside `{ancestor}`");
+                return self.lint("content_model", Level::Allow, span, message);
+            }
+        }
+        if name == "form"
+            && self
+                .ancestors
+                .iter()
+                .flatten()
+                .any(|ancestor| ancestor == "form")
+        {
+            let message = "`form` can't be inside another `form`".into();
+            self.lint("content_model", Level::Allow, span, message);
+        }
+    }
+
+    fn names(&mut self, original: &str, name: &str, element: &Element) {
+        if !ELEMENTS.contains(&name) {
+            // Custom elements can have any attributes.
+            if !name.contains('-') {
+                let message = match suggest(name, ELEMENTS.iter().copied()) {
+                    Some(suggestion) => {
+                        format!("`{original}` isn't an HTML element, did you mean `{suggestion}`?")
+                    }
+                    None => format!(
+                        "`{original}` isn't an HTML element, custom element names must contain a `-`"
+                    ),
+                };
+                self.lint("element_names", Level::Allow, element.name.span(), message);
+            }
+            return;
+        }
+        // Their attributes are defined by other standards.
+        if matches!(name, "math" | "svg") {
+            return;
+        }
+        let specific = ATTRIBUTES
+            .iter()
+            .find(|(element, _)| *element == name)
+            .map_or(&[][..], |(_, attributes)| attributes);
+        for (attribute, _) in pairs(&element.attributes) {
+            let (attribute, span) = match literal(attribute) {
+                Some(literal) => (literal, attribute.span()),
+                None => continue,
+            };
+            let lowercase = attribute.to_ascii_lowercase();
+            let prefixes = ATTRIBUTE_PREFIXES
+                .iter()
+                .copied()
+                .chain(self.options.attribute_prefixes.iter().map(String::as_str));
+            if GLOBAL_ATTRIBUTES.contains(&lowercase.as_str())
+                || specific.contains(&lowercase.as_str())
+                // Event handlers, namespaced attributes like `xml:lang` and framework specific
+                // ones like `@click`.
+                || lowercase.starts_with("on")
+                || lowercase.contains(':')
+                || lowercase.starts_with('@')
+                || prefixes.clone().any(|prefix| lowercase.starts_with(prefix))
+            {
+                continue;
+            }
+            let candidates = GLOBAL_ATTRIBUTES.iter().chain(specific).copied();
+            let message = match suggest(&lowercase, candidates) {
+                Some(suggestion) => format!(
+                    "`{attribute}` isn't an attribute of `{original}`, did you mean `{suggestion}`?"
+                ),
+                None => format!(
+                    "`{attribute}` isn't an attribute of `{original}`, custom attributes should start with `data-`"
+                ),
+            };
+            self.lint("attribute_names", Level::Allow, span, message);
+        }
+    }
+
+    fn values(&mut self, name: &str, element: &Element) {
+        if !ELEMENTS.contains(&name) || matches!(name, "math" | "svg") {
+            return;
+        }
+        for (attribute, value) in pairs(&element.attributes) {
+            let attribute = match literal(attribute) {
+                Some(attribute) => attribute.to_ascii_lowercase(),
+                None => continue,
+            };
+            let (values, string) = match (
+                VALUES.iter().find(|values| {
+                    values.attribute == attribute
+                        && (values.elements.is_empty() || values.elements.contains(&name))
+                }),
+                literal(value),
+            ) {
+                (Some(values), Some(string)) => (values, string),
+                _ => continue,
+            };
+            let tokens = if values.list {
+                string.split_ascii_whitespace().collect()
+            } else {
+                vec![string.trim()]
+            };
+            for token in tokens {
+                let lowercase = token.to_ascii_lowercase();
+                if values.values.contains(&lowercase.as_str())
+                    // Names of browsing contexts.
+                    || (attribute.ends_with("target") && !lowercase.starts_with('_'))
+                    || (attribute == "autocomplete" && lowercase.starts_with("section-"))
+                {
+                    continue;
+                }
+                let candidates = values
+                    .values
+                    .iter()
+                    .copied()
+                    .filter(|value| !value.is_empty());
+                let message = match suggest(&lowercase, candidates.clone()) {
+                    Some(suggestion) => format!(
+                        "`{token}` isn't a valid value for `{attribute}` on `{name}`, did you mean `{suggestion}`?"
+                    ),
+                    None if values.values.len() <= 8 => format!(
+                        "`{token}` isn't a valid value for `{attribute}` on `{name}`, expected {}",
+                        list(&candidates.collect::<Vec<_>>(), "or")
+                    ),
+                    None => format!("`{token}` isn't a valid value for `{attribute}` on `{name}`"),
+                };
+                self.lint("attribute_values", Level::Warn, value.span(), message);
+            }
+        }
+    }
+
+    fn a11y(&mut self, name: &str, element: &Element) {
+        let span = element.name.span();
+        if let Some(level) = name
+            .strip_prefix('h')
+            .and_then(|level| level.parse::<u8>().ok())
+            .filter(|level| (1..=6).contains(level))
+        {
+            if let Some(previous) = self.heading.filter(|previous| level > previous + 1) {
+                let message = format!(
+                    "`{name}` skips a heading level after `h{previous}`, use `h{}` instead",
+                    previous + 1
+                );
+                self.lint("skipped_heading_levels", Level::Allow, span, message);
+            }
+            self.heading = Some(level);
+        }
+        // Any attribute may be set by the ones we can't see.
+        if dynamic_attributes(element) {
+            return;
+        }
+        let missing = |name| attribute(element, name).is_none();
+        match name {
+            "img" if missing("alt") => {
+                let message =
+                    "`img` needs an `alt` attribute, use `alt = \"\"` for decorative images".into();
+                self.lint("missing_alt", Level::Allow, span, message);
+            }
+            "button" if missing("type") => {
+                let message =
+                    "`button` needs a `type` attribute, without one it submits its form".into();
+                self.lint("missing_button_type", Level::Allow, span, message);
+            }
+            "a" if missing("href") => {
+                let message =
+                    "`a` needs an `href` attribute, use a `button` for actions instead".into();
+                self.lint("missing_href", Level::Allow, span, message);
+            }
+            "html" if missing("lang") => {
+                let message = "`html` needs a `lang` attribute".into();
+                self.lint("missing_lang", Level::Allow, span, message);
+            }
+            "input" | "select" | "textarea" => {
+                let kind = match attribute(element, "type").map(literal) {
+                    Some(Some(kind)) => kind.to_ascii_lowercase(),
+                    Some(None) => return,
+                    None => String::new(),
+                };
+                // These are labelled by their value or aren't shown at all.
+                if name == "input"
+                    && matches!(
+                        kind.as_str(),
+                        "button" | "hidden" | "image" | "reset" | "submit"
+                    )
+                {
+                    return;
+                }
+                let labelled = match element.id.as_ref().or_else(|| attribute(element, "id")) {
+                    Some(id) => match literal(id) {
+                        Some(id) => self.labels.contains(&id),
+                        None => true,
+                    },
+                    None => false,
+                };
+                if !labelled
+                    && missing("aria-label")
+                    && missing("aria-labelledby")
+                    && !self
+                        .ancestors
+                        .iter()
+                        .flatten()
+                        .any(|ancestor| ancestor == "label")
+                {
+                    let message = format!("`{name}` needs a `label` or an `aria-label` attribute");
+                    self.lint("missing_label", Level::Allow, span, message);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects the literal `for` attributes of the `label` elements in `nodes`.
+fn labels(nodes: &[Node], labels: &mut Vec<String>) {
+    for node in nodes {
+        match node {
+            Node::Element(element) | Node::WrapIf(WrapIf { element, .. }) => {
+                if matches!(literal(&element.name), Some(name) if name.eq_ignore_ascii_case("label"))
+                {
+                    labels.extend(attribute(element, "for").and_then(literal));
+                }
+                self::labels(&element.children, labels);
+            }
+            Node::If(If { clauses, default }) => {
+                for IfClause { consequent, .. } in clauses {
+                    self::labels(consequent, labels);
+                }
+                if let Some(default) = default {
+                    self::labels(default, labels);
+                }
+            }
+            Node::Match(Match { clauses, .. }) => {
+                for MatchClause { consequent, .. } in clauses {
+                    self::labels(consequent, labels);
+                }
+            }
+            Node::For(for_) => self::labels(&for_.body, labels),
+            Node::Expr(_) | Node::Stmt(_) | Node::Interpolated(_) | Node::Entity(_) => {}
+        }
+    }
+}
+
+/// A literal id, where it's set and the level of `duplicate_ids` there.
+struct Id(String, Span, Level);
+
+/// Combines the ids found in the branches of an `@if` or `@match`.
+fn union(branches: Vec<Vec<Id>>) -> Vec<Id> {
+    let mut ids = Vec::<Id>::new();
+    for id in branches.into_iter().flatten() {
+        if !ids.iter().any(|other| other.0 == id.0) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// Returns the value of the attribute `name` of `element`.
+fn attribute<'a>(element: &'a Element, name: &str) -> Option<&'a syn::Expr> {
+    element.attributes.iter().find_map(|attribute| match attribute {
+        Attribute::One(attribute, value)
+            if matches!(literal(attribute), Some(attribute) if attribute.eq_ignore_ascii_case(name)) =>
+        {
+            Some(value)
+        }
+        _ => None,
+    })
+}
+
+/// Whether `element` may have attributes which aren't known at compile time, including ones
+/// which are only set under some condition.
+fn dynamic_attributes(element: &Element) -> bool {
+    element.attributes.iter().any(|attribute| match attribute {
+        Attribute::One(attribute, _) => literal(attribute).is_none(),
+        Attribute::Many(_) | Attribute::If(_) | Attribute::Match(_) => true,
+    })
+}
+
+/// Returns the names and values of `attributes`, including the ones in `@if` and `@match`.
+fn pairs(attributes: &[Attribute]) -> Vec<(&syn::Expr, &syn::Expr)> {
+    let mut pairs = Vec::new();
+    for attribute in attributes {
+        match attribute {
+            Attribute::One(name, value) => pairs.push((name, value)),
+            Attribute::Many(_) => {}
+            Attribute::If(If { clauses, default }) => {
+                for IfClause { consequent, .. } in clauses {
+                    pairs.extend(self::pairs(consequent));
+                }
+                if let Some(default) = default {
+                    pairs.extend(self::pairs(default));
+                }
+            }
+            Attribute::Match(Match { clauses, .. }) => {
+                for MatchClause { consequent, .. } in clauses {
+                    pairs.extend(self::pairs(consequent));
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Returns the candidate closest to `name` if it's close enough to be a typo of it.
+pub fn suggest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max = (name.chars().count() / 3).max(1);
+    candidates
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of insertions, deletions, substitutions and transpositions of adjacent characters
+/// needed to turn `a` into `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in table[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            table[i][j] = (table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1)
+                .min(table[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                table[i][j] = table[i][j].min(table[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    table[a.len()][b.len()]
+}
+
+/// Formats `names` like "`a`, `b` or `c`".
+fn list(names: &[&str], conjunction: &str) -> String {
+    let mut string = String::new();
+    for (index, name) in names.iter().enumerate() {
+        if index + 1 == names.len() && index > 0 {
+            string.push(' ');
+            string.push_str(conjunction);
+            string.push(' ');
+        } else if index > 0 {
+            string.push_str(", ");
+        }
+        string.push('`');
+        string.push_str(name);
+        string.push('`');
+    }
+    string
+}
+
+#[test]
+fn test() {
+    assert_eq!(distance("", "abc"), 3);
+    assert_eq!(distance("tyep", "type"), 1);
+    assert_eq!(distance("inptu", "input"), 1);
+    assert_eq!(distance("kitten", "sitting"), 3);
+    let candidates = ["href", "hreflang", "type"];
+    assert_eq!(suggest("hreff", candidates.iter().copied()), Some("href"));
+    assert_eq!(suggest("tpye", candidates.iter().copied()), Some("type"));
+    assert_eq!(suggest("src", candidates.iter().copied()), None);
+}
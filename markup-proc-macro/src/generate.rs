@@ -1,14 +1,29 @@
 use crate::ast::{
-    Attribute, Element, For, If, IfClause, IfClauseTest, Match, MatchClause, Node, Struct, Template,
+    Attribute, Component, Define, Definition, Derive, DeriveProps, Element, Entity, Enum, For, If,
+    IfClause, IfClauseTest, Match, MatchClause, Node, Options, PendingProps, Props, Struct,
+    Template, Variant, WrapIf,
 };
+use crate::check::check;
+use crate::html::{is_true_false, is_xml_char, BLOCK, RAW};
+use crate::minify::{self, Next};
 use proc_macro2::Span;
 use proc_macro2::TokenStream;
 use proc_macro2::TokenTree;
 use quote::quote_spanned;
 use quote::{quote, ToTokens};
+use std::rc::Rc;
 use syn::spanned::Spanned;
 use syn::Ident;
 
+impl ToTokens for Definition {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Definition::Struct(struct_) => struct_.to_tokens(tokens),
+            Definition::Enum(enum_) => enum_.to_tokens(tokens),
+        }
+    }
+}
+
 impl ToTokens for Struct {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Struct {
@@ -17,16 +32,18 @@ impl ToTokens for Struct {
             generics,
             where_clause,
             fields,
+            props,
             children,
             size_hint,
+            include,
+            options,
         } = self;
-        let mut stream = Stream::default();
+        tokens.extend(check(children, options));
+        let mut stream = Stream::new(options);
         let writer = Ident::new("__writer", name.span());
         children.generate(&mut stream, &writer);
         let built = stream.finish(&writer);
-        let (impl_generics, ty_generics, _) = generics.split_for_impl();
         let mut struct_fields = TokenStream::new();
-        let mut splat_fields = TokenStream::new();
         for field in fields {
             let attrs = &field.attrs;
             let name = field.ident.as_ref().unwrap();
@@ -36,17 +53,417 @@ impl ToTokens for Struct {
                 #(#attrs)*
                 pub #name: #ty,
             });
-            splat_fields.extend(quote_spanned! {
-                name.span() =>
-                #name,
-            });
         }
+        let splat_fields = splat(fields);
+        let props = bind(props);
         tokens.extend(quote_spanned! {
             name.span() =>
             #(#attributes)*
             pub struct #name #generics #where_clause {
                 #struct_fields
             }
+        });
+        if let Some(include) = include {
+            tokens.extend(quote_spanned! {
+                name.span() =>
+                // Makes Cargo rebuild the crate when the template changes.
+                const _: &str = include_str!(#include);
+            });
+        }
+        let where_clause = render_bounds(generics, where_clause, &[(fields, children)]);
+        tokens.extend(impls(
+            name,
+            generics,
+            &where_clause,
+            Some(quote!(#size_hint)),
+            quote_spanned! {
+                name.span() =>
+                let #name { #splat_fields } = self;
+                #props
+                #built
+            },
+            &writer,
+        ));
+    }
+}
+
+impl ToTokens for Enum {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Enum {
+            name,
+            attributes,
+            generics,
+            where_clause,
+            variants,
+            options,
+        } = self;
+        let writer = Ident::new("__writer", name.span());
+        let mut enum_variants = TokenStream::new();
+        let mut size_hints = TokenStream::new();
+        let mut arms = TokenStream::new();
+        for variant in variants {
+            let Variant {
+                name: variant_name,
+                attributes,
+                fields,
+                props,
+                children,
+                size_hint,
+            } = variant;
+            tokens.extend(check(children, options));
+            let mut stream = Stream::new(options);
+            children.generate(&mut stream, &writer);
+            let built = stream.finish(&writer);
+            let splat_fields = splat(fields);
+            let props = bind(props);
+            if fields.is_empty() {
+                enum_variants.extend(quote_spanned! {
+                    variant_name.span() =>
+                    #(#attributes)*
+                    #variant_name,
+                });
+            } else {
+                let mut variant_fields = TokenStream::new();
+                for field in fields {
+                    let attrs = &field.attrs;
+                    let name = field.ident.as_ref().unwrap();
+                    let ty = &field.ty;
+                    variant_fields.extend(quote_spanned! {
+                        name.span() =>
+                        #(#attrs)*
+                        #name: #ty,
+                    });
+                }
+                enum_variants.extend(quote_spanned! {
+                    variant_name.span() =>
+                    #(#attributes)*
+                    #variant_name { #variant_fields },
+                });
+            }
+            size_hints.extend(quote_spanned! {
+                variant_name.span() =>
+                Self::#variant_name { .. } => #size_hint,
+            });
+            arms.extend(quote_spanned! {
+                variant_name.span() =>
+                Self::#variant_name { #splat_fields } => {
+                    #props
+                    #built
+                }
+            });
+        }
+        tokens.extend(quote_spanned! {
+            name.span() =>
+            #(#attributes)*
+            pub enum #name #generics #where_clause {
+                #enum_variants
+            }
+        });
+        let rendered = variants
+            .iter()
+            .map(|variant| (&variant.fields[..], &variant.children[..]))
+            .collect::<Vec<_>>();
+        let where_clause = render_bounds(generics, where_clause, &rendered);
+        tokens.extend(impls(
+            name,
+            generics,
+            &where_clause,
+            Some(quote_spanned!(name.span() => match self { #size_hints })),
+            quote_spanned!(name.span() => match self { #arms }),
+            &writer,
+        ));
+    }
+}
+
+impl ToTokens for Define {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Define {
+            definitions,
+            pending,
+        } = self;
+        match pending {
+            Some(PendingProps {
+                path,
+                ty,
+                generics,
+                where_clause,
+                fields,
+                tokens: input,
+            }) => {
+                // The macro has the same name as the struct, so any path which names the struct,
+                // including one brought into scope by `use`, names the macro too.
+                let span = path.segments.last().unwrap().ident.span();
+                tokens.extend(quote_spanned! {
+                    span =>
+                    #path! { [#([#(#fields),*])*] #input }
+                });
+                // Without the derive, the macro above is missing, which rustc reports without
+                // saying why, so this reports the missing `markup::Props` impl along with it.
+                let (impl_generics, _, _) = generics.split_for_impl();
+                let check = quote_spanned!(ty.span() => __markup_props::<#ty>());
+                tokens.extend(quote! {
+                    const _: () = {
+                        #[allow(dead_code)]
+                        fn __markup_check #impl_generics () #where_clause {
+                            fn __markup_props<T: ::markup::Props + ?Sized>() {}
+                            #check;
+                        }
+                    };
+                });
+            }
+            None => tokens.extend(quote!(#(#definitions)*)),
+        }
+    }
+}
+
+/// The name of the macro which `#[derive(markup::Props)]` defines for the struct `name`.
+fn props_macro(name: &Ident) -> Ident {
+    quote::format_ident!("__markup_props_{}", name, span = name.span())
+}
+
+impl ToTokens for DeriveProps {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let DeriveProps {
+            name,
+            generics,
+            fields,
+        } = self;
+        let props_macro = props_macro(name);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        // The macro passes the fields back to `define!`, which can't see the struct itself. It's
+        // imported into the module under the name of the struct, so that it's found through the
+        // same paths and `use` declarations as the struct.
+        tokens.extend(quote! {
+            #[doc(hidden)]
+            #[allow(unused_macros)]
+            macro_rules! #props_macro {
+                ([$($fields:tt)*] $($input:tt)*) => {
+                    ::markup::define! { @fields [$($fields)* [#(#fields),*]] $($input)* }
+                };
+            }
+            #[allow(unused_imports)]
+            pub(crate) use #props_macro as #name;
+            impl #impl_generics ::markup::Props for #name #ty_generics #where_clause {}
+        });
+    }
+}
+
+impl ToTokens for Derive {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Derive {
+            name,
+            generics,
+            fields,
+            children,
+            options,
+        } = self;
+        tokens.extend(check(children, options));
+        let mut stream = Stream::new(options);
+        let writer = Ident::new("__writer", name.span());
+        children.generate(&mut stream, &writer);
+        let built = stream.finish(&writer);
+        tokens.extend(impls(
+            name,
+            generics,
+            &generics.where_clause,
+            None,
+            quote_spanned! {
+                name.span() =>
+                // The template needn't use every field of the type.
+                #[allow(unused_variables)]
+                let #name { #(#fields,)* } = self;
+                #built
+            },
+            &writer,
+        ));
+    }
+}
+
+fn splat(fields: &[syn::Field]) -> TokenStream {
+    let mut splat_fields = TokenStream::new();
+    for field in fields {
+        let name = field.ident.as_ref().unwrap();
+        splat_fields.extend(quote_spanned! {
+            name.span() =>
+            #name,
+        });
+    }
+    splat_fields
+}
+
+/// Binds the fields of `..name: Type { fields }` fields, after they've been bound by `splat`, by
+/// destructuring them so that rustc checks the listed fields against the struct.
+fn bind(props: &[Props]) -> TokenStream {
+    let mut bindings = TokenStream::new();
+    for Props {
+        name,
+        path,
+        fields,
+        listed,
+        ..
+    } in props
+    {
+        // The template needn't use every field of the struct if they weren't listed.
+        let allow = (!listed).then(|| quote!(#[allow(unused_variables)]));
+        bindings.extend(quote_spanned! {
+            name.span() =>
+            #allow
+            let #path { #(#fields,)* .. } = #name;
+        });
+    }
+    bindings
+}
+
+/// Adds a `markup::Render` bound for each type parameter which is the type of a field (or a
+/// reference to it) that is rendered directly with `@field`, so that a missing bound is reported
+/// where the template is used instead of inside the generated code.
+fn render_bounds(
+    generics: &syn::Generics,
+    where_clause: &Option<syn::WhereClause>,
+    rendered: &[(&[syn::Field], &[Node])],
+) -> Option<syn::WhereClause> {
+    let mut params = Vec::new();
+    for (fields, children) in rendered {
+        let mut names = Vec::new();
+        rendered_names(children, &mut Vec::new(), &mut names);
+        for field in *fields {
+            let ty = match &field.ty {
+                syn::Type::Reference(reference) => &*reference.elem,
+                ty => ty,
+            };
+            let param = match ty {
+                syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident(),
+                _ => None,
+            };
+            if let (Some(param), Some(name)) = (param, &field.ident) {
+                if generics
+                    .type_params()
+                    .any(|type_param| &type_param.ident == param)
+                    && names.contains(&name.to_string())
+                    && !params.contains(&param)
+                {
+                    params.push(param);
+                }
+            }
+        }
+    }
+    if params.is_empty() {
+        return where_clause.clone();
+    }
+    let mut where_clause = where_clause.clone().unwrap_or_else(|| syn::WhereClause {
+        where_token: Default::default(),
+        predicates: Default::default(),
+    });
+    for param in params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote_spanned!(param.span() => #param: ::markup::Render));
+    }
+    Some(where_clause)
+}
+
+/// Collects the names rendered with `@name` in `nodes`, leaving out the ones shadowed by
+/// bindings in the template.
+fn rendered_names(nodes: &[Node], shadowed: &mut Vec<String>, names: &mut Vec<String>) {
+    let len = shadowed.len();
+    for node in nodes {
+        match node {
+            Node::Element(element) | Node::WrapIf(WrapIf { element, .. }) => {
+                rendered_names(&element.children, shadowed, names)
+            }
+            Node::If(If { clauses, default }) => {
+                for IfClause { test, consequent } in clauses {
+                    match test {
+                        IfClauseTest::Expr(_) => rendered_names(consequent, shadowed, names),
+                        IfClauseTest::Let(pat, _) => scoped(pat, consequent, shadowed, names),
+                    }
+                }
+                if let Some(default) = default {
+                    rendered_names(default, shadowed, names);
+                }
+            }
+            Node::Match(Match { clauses, .. }) => {
+                for MatchClause {
+                    pat, consequent, ..
+                } in clauses
+                {
+                    scoped(pat, consequent, shadowed, names);
+                }
+            }
+            Node::For(For { pat, body, .. }) => scoped(pat, body, shadowed, names),
+            Node::Stmt(syn::Stmt::Local(local)) => bindings(&local.pat, shadowed),
+            Node::Stmt(_) | Node::Entity(_) => {}
+            Node::Expr(expr) => rendered_name(expr, shadowed, names),
+            Node::Interpolated(parts) => {
+                for part in parts {
+                    rendered_name(part, shadowed, names);
+                }
+            }
+        }
+    }
+    shadowed.truncate(len);
+}
+
+fn scoped(pat: &syn::Pat, nodes: &[Node], shadowed: &mut Vec<String>, names: &mut Vec<String>) {
+    let len = shadowed.len();
+    bindings(pat, shadowed);
+    rendered_names(nodes, shadowed, names);
+    shadowed.truncate(len);
+}
+
+fn rendered_name(expr: &syn::Expr, shadowed: &[String], names: &mut Vec<String>) {
+    if let syn::Expr::Path(syn::ExprPath {
+        qself: None, path, ..
+    }) = expr
+    {
+        if let Some(ident) = path.get_ident() {
+            let ident = ident.to_string();
+            if !shadowed.contains(&ident) {
+                names.push(ident);
+            }
+        }
+    }
+}
+
+/// Collects the names bound by `pat`.
+fn bindings(pat: &syn::Pat, names: &mut Vec<String>) {
+    match pat {
+        syn::Pat::Ident(ident) => {
+            names.push(ident.ident.to_string());
+            if let Some((_, pat)) = &ident.subpat {
+                bindings(pat, names);
+            }
+        }
+        syn::Pat::Or(or) => or.cases.iter().for_each(|pat| bindings(pat, names)),
+        syn::Pat::Paren(paren) => bindings(&paren.pat, names),
+        syn::Pat::Reference(reference) => bindings(&reference.pat, names),
+        syn::Pat::Slice(slice) => slice.elems.iter().for_each(|pat| bindings(pat, names)),
+        syn::Pat::Struct(struct_) => struct_
+            .fields
+            .iter()
+            .for_each(|field| bindings(&field.pat, names)),
+        syn::Pat::Tuple(tuple) => tuple.elems.iter().for_each(|pat| bindings(pat, names)),
+        syn::Pat::TupleStruct(tuple) => tuple.elems.iter().for_each(|pat| bindings(pat, names)),
+        syn::Pat::Type(type_) => bindings(&type_.pat, names),
+        _ => {}
+    }
+}
+
+fn impls(
+    name: &Ident,
+    generics: &syn::Generics,
+    where_clause: &Option<syn::WhereClause>,
+    size_hint: Option<TokenStream>,
+    body: TokenStream,
+    writer: &Ident,
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let mut tokens = TokenStream::new();
+    // Derived impls don't get an inherent `to_string` as it could clash with one the type already
+    // has. `Display` provides one anyway.
+    if let Some(size_hint) = size_hint {
+        tokens.extend(quote_spanned! {
+            name.span() =>
             impl #impl_generics #name #ty_generics #where_clause {
                 #[inline]
                 #[allow(clippy::inherent_to_string_shadow_display)]
@@ -58,50 +475,248 @@ impl ToTokens for Struct {
                     string
                 }
             }
-            impl #impl_generics ::markup::Render for #name #ty_generics #where_clause {
-                fn render(&self, #writer: &mut impl std::fmt::Write) -> std::fmt::Result {
-                    let #name { #splat_fields } = self;
-                    #built
-                    Ok(())
-                }
+        });
+    }
+    tokens.extend(quote_spanned! {
+        name.span() =>
+        impl #impl_generics ::markup::Render for #name #ty_generics #where_clause {
+            fn render(&self, #writer: &mut impl std::fmt::Write) -> std::fmt::Result {
+                #body
+                Ok(())
             }
-            impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
-                #[inline]
-                fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        }
+        impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
+            #[inline]
+            fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if fmt.alternate() {
+                    ::markup::Render::render(&::markup::pretty(self), fmt)
+                } else {
                     ::markup::Render::render(self, fmt)
                 }
             }
+        }
+    });
+    tokens
+}
+
+impl ToTokens for Component {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Component {
+            attributes,
+            visibility,
+            signature,
+            statements,
+            children,
+            options,
+        } = self;
+        tokens.extend(check(children, options));
+        let writer = Ident::new("__writer", Span::call_site());
+        let mut stream = Stream::new(options);
+        children.generate(&mut stream, &writer);
+        let built = stream.finish(&writer);
+        tokens.extend(quote_spanned! {
+            signature.ident.span() =>
+            #(#attributes)*
+            #[allow(non_snake_case)]
+            #visibility #signature {
+                #(#statements)*
+                ::markup::from_fn(move |mut ___writer| {
+                    let mut #writer = &mut ___writer;
+                    #built
+                    Ok(())
+                })
+            }
         })
     }
 }
 
 impl ToTokens for Template {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let Self { children } = self;
+        let Self {
+            parameters,
+            children,
+            include,
+        } = self;
+        let errors = check(children, &Options::default());
         let writer = Ident::new("__writer", Span::call_site());
-        let mut stream = Stream::default();
+        let mut stream = Stream::new(&Options::default());
         children.generate(&mut stream, &writer);
         let built = stream.finish(&writer);
+        let include = include.as_ref().map(|include| {
+            quote! {
+                // Makes Cargo rebuild the crate when the template changes.
+                const _: &str = include_str!(#include);
+            }
+        });
+        let parameters = match parameters {
+            Some(parameters) => parameters,
+            None => {
+                tokens.extend(quote! {{
+                    #errors
+                    #include
+                    ::markup::new(move |mut ___writer| {
+                        let mut #writer = &mut ___writer;
+                        #built
+                        Ok(())
+                    })
+                }});
+                return;
+            }
+        };
+        let pats = parameters.iter().map(|parameter| &parameter.pat);
+        let tys = parameters.iter().map(|parameter| &parameter.ty);
+        let mut lifetimes = Vec::new();
+        let named = parameters
+            .iter()
+            .map(|parameter| {
+                let mut ty = parameter.ty.clone();
+                name_elided_lifetimes(&mut ty, &mut lifetimes);
+                ty
+            })
+            .collect::<Vec<_>>();
+        let binder = (!lifetimes.is_empty()).then(|| quote!(for<#(#lifetimes),*>));
+        let arguments = (0..parameters.len())
+            .map(|index| Ident::new(&format!("__argument{}", index), Span::call_site()))
+            .collect::<Vec<_>>();
+        // The template is compiled to a closure which takes the arguments by reference and binds
+        // them by reference, like the fields of a `define!` template. The value returned to the
+        // user is another closure which moves its arguments and a shared handle to the template
+        // into a `Render` value, so that it can be called any number of times without cloning
+        // what the template captures. That value borrows from the arguments, which a closure's
+        // signature can only express when it's given by the type the closure is coerced to, so
+        // the closure is boxed as a `dyn Fn` over the lifetimes which the argument types elide.
         tokens.extend(quote! {{
-            ::markup::new(move |mut ___writer| {
+            #errors
+            #include
+            let __template = ::std::rc::Rc::new(move |
+                mut ___writer: &mut dyn ::std::fmt::Write,
+                __arguments: &(#(#tys,)*)
+            | -> ::std::fmt::Result {
+                let (#(#pats,)*) = __arguments;
                 let mut #writer = &mut ___writer;
                 #built
                 Ok(())
-            })
+            });
+            let __new: ::std::boxed::Box<
+                dyn #binder Fn(#(#named),*) -> ::markup::Call<_, (#(#named,)*)> + '_
+            > = ::std::boxed::Box::new(move |#(#arguments),*| {
+                ::markup::call(::std::rc::Rc::clone(&__template), (#(#arguments,)*))
+            });
+            __new
         }})
     }
 }
 
+/// Names each lifetime elided in `ty`, like the one in `&str` or `Cow<'_, str>`, adding the names
+/// to `lifetimes`.
+fn name_elided_lifetimes(ty: &mut syn::Type, lifetimes: &mut Vec<syn::Lifetime>) {
+    fn name(span: Span, lifetimes: &mut Vec<syn::Lifetime>) -> syn::Lifetime {
+        let lifetime = syn::Lifetime::new(&format!("'__markup{}", lifetimes.len()), span);
+        lifetimes.push(lifetime.clone());
+        lifetime
+    }
+    match ty {
+        syn::Type::Reference(reference) => {
+            match &mut reference.lifetime {
+                Some(lifetime) if lifetime.ident == "_" => {
+                    *lifetime = name(lifetime.span(), lifetimes)
+                }
+                Some(_) => {}
+                None => reference.lifetime = Some(name(reference.and_token.span, lifetimes)),
+            }
+            name_elided_lifetimes(&mut reference.elem, lifetimes);
+        }
+        syn::Type::Path(path) => {
+            for segment in &mut path.path.segments {
+                if let syn::PathArguments::AngleBracketed(arguments) = &mut segment.arguments {
+                    for argument in &mut arguments.args {
+                        match argument {
+                            syn::GenericArgument::Lifetime(lifetime) if lifetime.ident == "_" => {
+                                *lifetime = name(lifetime.span(), lifetimes)
+                            }
+                            syn::GenericArgument::Type(ty) => name_elided_lifetimes(ty, lifetimes),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        syn::Type::Tuple(tuple) => {
+            for ty in &mut tuple.elems {
+                name_elided_lifetimes(ty, lifetimes);
+            }
+        }
+        syn::Type::Slice(slice) => name_elided_lifetimes(&mut slice.elem, lifetimes),
+        syn::Type::Array(array) => name_elided_lifetimes(&mut array.elem, lifetimes),
+        syn::Type::Paren(paren) => name_elided_lifetimes(&mut paren.elem, lifetimes),
+        syn::Type::Group(group) => name_elided_lifetimes(&mut group.elem, lifetimes),
+        _ => {}
+    }
+}
+
 trait Generate {
     fn generate(&self, stream: &mut Stream, writer: &Ident);
 }
 
-impl<T: Generate> Generate for Vec<T> {
+impl Generate for Vec<Node> {
     fn generate(&self, stream: &mut Stream, writer: &Ident) {
-        for x in self {
-            x.generate(stream, writer)
+        nodes(self, None, stream, writer)
+    }
+}
+
+/// Generates the children of the element `parent`, or of the template if it's `None`.
+fn nodes(nodes: &[Node], parent: Option<&str>, stream: &mut Stream, writer: &Ident) {
+    for (index, node) in nodes.iter().enumerate() {
+        match node {
+            Node::Element(element) if stream.options.omit_optional_tags => {
+                let omit = match literal(&element.name) {
+                    Some(name) => minify::optional_end_tag(
+                        &name.to_ascii_lowercase(),
+                        next(stream, &nodes[index + 1..], parent),
+                    ),
+                    None => false,
+                };
+                element.generate_omitting(omit, stream, writer)
+            }
+            _ => node.generate(stream, writer),
+        }
+    }
+}
+
+/// Finds what will be output after an element followed by `nodes` inside `parent`.
+fn next(stream: &Stream, nodes: &[Node], parent: Option<&str>) -> Next {
+    for node in nodes {
+        match node {
+            Node::Stmt(_) => continue,
+            Node::Expr(expr)
+                if stream.options.minify
+                    && matches!(literal(expr), Some(text) if text.trim_matches(is_whitespace).is_empty()) =>
+            {
+                continue
+            }
+            Node::Element(element) => {
+                return literal(&element.name)
+                    .map_or(Next::Other, |name| Next::Element(name.to_ascii_lowercase()))
+            }
+            _ => return Next::Other,
         }
     }
+    parent.map_or(Next::Other, |parent| Next::End(parent.into()))
+}
+
+/// Returns the value of `expr` if it's a string literal.
+pub fn literal(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit_str),
+            ..
+        }) => Some(lit_str.value()),
+        _ => None,
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    c.is_ascii_whitespace()
 }
 
 impl Generate for Node {
@@ -111,22 +726,128 @@ impl Generate for Node {
             Node::If(if_) => if_.generate(stream, writer),
             Node::Match(match_) => match_.generate(stream, writer),
             Node::For(for_) => for_.generate(stream, writer),
-            Node::Expr(expr) => stream.expr(expr, writer),
+            Node::Expr(expr) => stream.text(expr, writer),
             Node::Stmt(stmt) => stream.extend(stmt.into_token_stream(), writer),
+            Node::Entity(entity) => entity.generate(stream, writer),
+            Node::WrapIf(wrap_if) => wrap_if.generate(stream, writer),
+            Node::Interpolated(parts) => {
+                for part in parts {
+                    stream.text(part, writer);
+                }
+            }
+        }
+    }
+}
+
+impl Generate for Entity {
+    fn generate(&self, stream: &mut Stream, _writer: &Ident) {
+        // XML only has the named references for the characters it escapes.
+        if stream.options.xml
+            && !matches!(
+                self.reference.as_str(),
+                "&amp;" | "&lt;" | "&gt;" | "&quot;" | "&apos;"
+            )
+        {
+            for c in self.value.chars().filter(|&c| is_xml_char(c)) {
+                stream.raw(&format!("&#{};", c as u32));
+            }
+        } else {
+            stream.raw(&self.reference);
         }
     }
 }
 
 impl Generate for Element {
     fn generate(&self, stream: &mut Stream, writer: &Ident) {
+        self.generate_omitting(false, stream, writer)
+    }
+}
+
+impl Element {
+    /// Generates the element, leaving out its end tag if `omit_end_tag` is set.
+    fn generate_omitting(&self, omit_end_tag: bool, stream: &mut Stream, writer: &Ident) {
+        let tag = literal(&self.name).map(|name| name.to_ascii_lowercase());
+        let tag = tag.as_deref();
+        let block = matches!(tag, Some(name) if BLOCK.contains(&name));
+        let name = self.bind_name(stream, writer);
+        self.start_tag(&name, stream, writer);
+
+        let raw = matches!(tag, Some(name) if RAW.contains(&name));
+        if raw {
+            stream.preserve += 1;
+        }
+        match tag {
+            Some(name @ ("script" | "style")) if stream.options.minify && minifiable(self) => {
+                for child in &self.children {
+                    let (value, escape) = static_content(child).unwrap();
+                    let value = if name == "script" {
+                        minify::js(&value)
+                    } else {
+                        minify::css(&value)
+                    };
+                    if escape {
+                        stream.escaped(&value);
+                    } else {
+                        stream.raw(&value);
+                    }
+                }
+            }
+            // The children are in a block of their own so that the name of a nested element
+            // doesn't shadow this one.
+            None => stream.braced(|stream| nodes(&self.children, tag, stream, writer), writer),
+            _ => nodes(&self.children, tag, stream, writer),
+        }
+        if self.close && !omit_end_tag {
+            if block {
+                stream.boundary();
+            }
+            if raw {
+                stream.preserve -= 1;
+            }
+            self.end_tag(&name, stream, writer);
+        } else if raw {
+            stream.preserve -= 1;
+        }
+    }
+
+    /// Returns the name of the element, after binding it to a variable if it isn't known at
+    /// compile time, so that it's evaluated once for the check and both tags.
+    fn bind_name(&self, stream: &mut Stream, writer: &Ident) -> syn::Expr {
+        if literal(&self.name).is_some() {
+            return self.name.clone();
+        }
+        let span = self.name.span();
+        let name = &self.name;
+        stream.extend(quote_spanned!(span => let __tag = &(#name);), writer);
+        syn::parse_quote_spanned!(span => __tag)
+    }
+
+    fn start_tag(&self, name: &syn::Expr, stream: &mut Stream, writer: &Ident) {
         let Element {
-            name,
+            lints: _,
+            name: _,
             id,
             classes,
             attributes,
-            children,
+            children: _,
             close,
         } = self;
+        let tag = literal(name).map(|name| name.to_ascii_lowercase());
+        let tag = tag.as_deref();
+        let block = matches!(tag, Some(name) if BLOCK.contains(&name));
+        if tag.is_none() && !stream.options.xml {
+            stream.extend(
+                quote_spanned! {
+                    name.span() =>
+                    #[cfg(debug_assertions)]
+                    ::markup::check_element(#name, #close);
+                },
+                writer,
+            );
+        }
+        if block {
+            stream.boundary();
+        }
         stream.raw("<");
         stream.expr(name, writer);
         if let Some(id) = id {
@@ -148,74 +869,216 @@ impl Generate for Element {
             stream.raw("\"");
         }
 
-        fn attr(stream: &mut Stream, name: &syn::Expr, expr: &syn::Expr, writer: &Ident) {
-            let span = expr.span();
-            let value = Ident::new("__value", span);
-            stream.extend(quote_spanned!(span => let #value = #expr;), writer);
-            stream.extend(
-                quote_spanned! {
-                    span =>
-                    if ::markup::RenderAttributeValue::is_none(&#value) ||
-                       ::markup::RenderAttributeValue::is_false(&#value)
-                },
-                writer,
-            );
-            stream.braced(|_| {}, writer);
-            stream.extend(
-                quote_spanned!(span => else if ::markup::RenderAttributeValue::is_true(&#value)),
-                writer,
-            );
-            stream.braced(
-                |stream| {
-                    stream.raw(" ");
-                    stream.expr(name, writer);
-                },
-                writer,
-            );
-            stream.extend(quote_spanned!(span => else), writer);
-            stream.braced(
-                |stream| {
-                    stream.raw(" ");
-                    stream.expr(name, writer);
-                    stream.raw("=\"");
-                    stream.expr(&syn::parse_quote_spanned!(span => #value), writer);
-                    stream.raw("\"");
-                },
+        attributes.generate(stream, writer);
+
+        if stream.options.xml && !close {
+            stream.raw("/>");
+        } else {
+            stream.raw(">");
+        }
+        if block {
+            stream.boundary();
+        }
+    }
+
+    fn end_tag(&self, name: &syn::Expr, stream: &mut Stream, writer: &Ident) {
+        stream.raw("</");
+        stream.expr(name, writer);
+        stream.raw(">");
+        if matches!(literal(name), Some(name) if BLOCK.contains(&name.to_ascii_lowercase().as_str()))
+        {
+            stream.boundary();
+        }
+    }
+}
+
+impl Generate for WrapIf {
+    fn generate(&self, stream: &mut Stream, writer: &Ident) {
+        let WrapIf { test, element } = self;
+        let wrap = Ident::new("__wrap", Span::call_site());
+        // The name is bound before the test so that it can be used by both tags.
+        let name = element.bind_name(stream, writer);
+        match test {
+            IfClauseTest::Expr(expr) => {
+                stream.extend(quote_spanned!(expr.span() => let #wrap = if #expr), writer)
+            }
+            IfClauseTest::Let(pattern, expr) => stream.extend(
+                quote_spanned!(pattern.span() => let #wrap = if let #pattern = #expr),
                 writer,
-            );
+            ),
+        }
+        stream.braced(
+            |stream| {
+                element.start_tag(&name, stream, writer);
+                stream.extend(quote!(true), writer);
+            },
+            writer,
+        );
+        stream.extend(quote!(else { false };), writer);
+        // The children are in a block of their own so that the `__wrap` of a nested `@wrap_if`
+        // doesn't shadow this one. Whitespace is kept if the element keeps it, as it may be
+        // wrapped.
+        let raw = matches!(literal(&element.name), Some(name) if RAW.contains(&name.to_ascii_lowercase().as_str()));
+        stream.braced(
+            |stream| {
+                stream.preserve += raw as usize;
+                nodes(&element.children, None, stream, writer);
+            },
+            writer,
+        );
+        stream.extend(quote!(if #wrap), writer);
+        stream.braced(|stream| element.end_tag(&name, stream, writer), writer);
+    }
+}
+
+impl Generate for Vec<Attribute> {
+    fn generate(&self, stream: &mut Stream, writer: &Ident) {
+        for attribute in self {
+            attribute.generate(stream, writer);
         }
+    }
+}
 
-        for attribute in attributes {
-            match attribute {
-                Attribute::One(name, value) => attr(stream, name, value, writer),
-                Attribute::Many(iter) => {
-                    let span = iter.span();
-                    let name = syn::parse_quote_spanned!(span => __name);
-                    let value = syn::parse_quote_spanned!(span => __value);
-                    stream.extend(quote_spanned!(span => for (#name, #value) in #iter), writer);
-                    stream.braced(
-                        |stream| {
-                            attr(stream, &name, &value, writer);
-                        },
-                        writer,
-                    );
-                }
+impl Generate for Attribute {
+    fn generate(&self, stream: &mut Stream, writer: &Ident) {
+        match self {
+            Attribute::One(name, value) => attr(stream, name, value, writer),
+            Attribute::Many(iter) => {
+                let span = iter.span();
+                let name = syn::parse_quote_spanned!(span => __name);
+                let value = syn::parse_quote_spanned!(span => __value);
+                stream.extend(quote_spanned!(span => for (#name, #value) in #iter), writer);
+                stream.braced(
+                    |stream| {
+                        attr(stream, &name, &value, writer);
+                    },
+                    writer,
+                );
             }
+            Attribute::If(if_) => if_.generate(stream, writer),
+            Attribute::Match(match_) => match_.generate(stream, writer),
         }
+    }
+}
 
-        stream.raw(">");
+fn attr(stream: &mut Stream, name: &syn::Expr, expr: &syn::Expr, writer: &Ident) {
+    let span = expr.span();
+    // Names which aren't known at compile time are evaluated once, as they're needed to check
+    // whether booleans are written out as well as to render the attribute.
+    let name = &match name {
+        syn::Expr::Path(_) => name.clone(),
+        _ if literal(name).is_some() => name.clone(),
+        _ => {
+            let name_span = name.span();
+            stream.extend(quote_spanned!(name_span => let __name = &(#name);), writer);
+            syn::parse_quote_spanned!(name_span => __name)
+        }
+    };
+    let value = Ident::new("__value", span);
+    stream.extend(quote_spanned!(span => let #value = #expr;), writer);
+    stream.extend(
+        quote_spanned!(span => if ::markup::RenderAttributeValue::is_none(&#value)),
+        writer,
+    );
+    stream.braced(|_| {}, writer);
+    // Booleans are written out for these, as `false` isn't the same as leaving them out.
+    let true_false = literal(name).map(|name| is_true_false(name.as_bytes()));
+    if true_false.is_none() {
+        stream.extend(
+            quote_spanned! {
+                span =>
+                else if (::markup::RenderAttributeValue::is_true(&#value) ||
+                         ::markup::RenderAttributeValue::is_false(&#value)) &&
+                        ::markup::is_true_false(&#name)
+            },
+            writer,
+        );
+        stream.braced(|stream| attr_value(stream, name, &value, writer), writer);
+    }
+    if true_false != Some(true) {
+        stream.extend(
+            quote_spanned!(span => else if ::markup::RenderAttributeValue::is_false(&#value)),
+            writer,
+        );
+        stream.braced(|_| {}, writer);
+        stream.extend(
+            quote_spanned!(span => else if ::markup::RenderAttributeValue::is_true(&#value)),
+            writer,
+        );
+        stream.braced(
+            |stream| {
+                stream.raw(" ");
+                stream.expr(name, writer);
+                // XML has no boolean attributes, so the value is repeated like in XHTML.
+                if stream.options.xml {
+                    stream.raw("=\"");
+                    stream.expr(name, writer);
+                    stream.raw("\"");
+                }
+            },
+            writer,
+        );
+    }
+    stream.extend(quote_spanned!(span => else), writer);
+    stream.braced(|stream| attr_value(stream, name, &value, writer), writer);
+}
 
-        children.generate(stream, writer);
+fn attr_value(stream: &mut Stream, name: &syn::Expr, value: &Ident, writer: &Ident) {
+    stream.raw(" ");
+    stream.expr(name, writer);
+    stream.raw("=\"");
+    stream.expr(&syn::parse_quote_spanned!(value.span() => #value), writer);
+    stream.raw("\"");
+}
 
-        if *close {
-            stream.raw("</");
-            stream.expr(name, writer);
-            stream.raw(">");
+/// Whether the contents of a `script` or `style` element are static and in a language we can
+/// minify.
+fn minifiable(element: &Element) -> bool {
+    let types: &[&str] = match literal(&element.name).map(|name| name.to_ascii_lowercase()) {
+        Some(name) if name == "script" => {
+            &["", "application/javascript", "module", "text/javascript"]
+        }
+        _ => &["", "text/css"],
+    };
+    element.attributes.iter().all(|attribute| match attribute {
+        Attribute::One(attribute, value) => {
+            !matches!(literal(attribute), Some(attribute) if attribute.eq_ignore_ascii_case("type"))
+                || matches!(literal(value), Some(value) if types.contains(&value.to_ascii_lowercase().as_str()))
         }
+        Attribute::Many(_) | Attribute::If(_) | Attribute::Match(_) => false,
+    }) && element
+        .children
+        .iter()
+        .all(|child| static_content(child).is_some())
+}
+
+/// Returns the text of a string literal node or a `markup::raw("...")` call, along with whether
+/// it's escaped when rendered.
+fn static_content(node: &Node) -> Option<(String, bool)> {
+    match node {
+        Node::Expr(syn::Expr::Call(call)) => match (&*call.func, call.args.first()) {
+            (syn::Expr::Path(path), Some(arg))
+                if call.args.len() == 1
+                    && path
+                        .path
+                        .segments
+                        .iter()
+                        .map(|segment| segment.ident.to_string())
+                        .eq(["markup", "raw"]) =>
+            {
+                literal(arg).map(|value| (value, false))
+            }
+            _ => None,
+        },
+        Node::Expr(expr) => literal(expr).map(|value| (value, true)),
+        _ => None,
     }
 }
 
-impl Generate for If {
+impl<T> Generate for If<T>
+where
+    Vec<T>: Generate,
+{
     fn generate(&self, stream: &mut Stream, writer: &Ident) {
         let mut first = true;
         for clause in &self.clauses {
@@ -247,7 +1110,10 @@ impl Generate for If {
     }
 }
 
-impl Generate for Match {
+impl<T> Generate for Match<T>
+where
+    Vec<T>: Generate,
+{
     fn generate(&self, stream: &mut Stream, writer: &Ident) {
         let Match { expr, clauses, .. } = self;
         stream.extend(quote_spanned!(expr.span() => match #expr), writer);
@@ -280,21 +1146,71 @@ impl Generate for For {
     }
 }
 
-#[derive(Default)]
 struct Stream {
     stream: TokenStream,
     buffer: String,
+    options: Rc<Options>,
+    // How many elements whose whitespace must be kept we're inside.
+    preserve: usize,
+    // Whether whitespace at the start of the next text can be dropped.
+    trim: bool,
 }
 
 impl Stream {
+    fn new(options: &Options) -> Stream {
+        Stream {
+            stream: TokenStream::new(),
+            buffer: String::new(),
+            options: Rc::new(options.clone()),
+            preserve: 0,
+            trim: false,
+        }
+    }
+
     fn raw(&mut self, str: &str) {
+        self.trim &= str.is_empty();
         self.buffer.push_str(str);
     }
 
     fn escaped(&mut self, str: &str) {
         let mut string = String::new();
         crate::escape::escape(str, &mut string).unwrap();
-        self.buffer.push_str(&string);
+        if self.options.xml {
+            string.retain(is_xml_char);
+        }
+        self.raw(&string);
+    }
+
+    /// Like `expr`, but for text content, whose whitespace is collapsed when minifying.
+    fn text(&mut self, expr: &syn::Expr, writer: &Ident) {
+        match literal(expr) {
+            Some(value) if self.options.minify && self.preserve == 0 => {
+                let mut text = String::new();
+                let mut space = self.trim || self.buffer.ends_with(is_whitespace);
+                for c in value.chars() {
+                    if !is_whitespace(c) {
+                        text.push(c);
+                        space = false;
+                    } else if !space {
+                        text.push(' ');
+                        space = true;
+                    }
+                }
+                if !text.is_empty() {
+                    self.escaped(&text);
+                }
+            }
+            _ => self.expr(expr, writer),
+        }
+    }
+
+    /// Marks the start or end of a block level element, where whitespace isn't significant.
+    fn boundary(&mut self) {
+        if self.options.minify && self.preserve == 0 {
+            let len = self.buffer.trim_end_matches(is_whitespace).len();
+            self.buffer.truncate(len);
+            self.trim = true;
+        }
     }
 
     fn expr(&mut self, expr: &syn::Expr, writer: &Ident) {
@@ -303,6 +1219,13 @@ impl Stream {
                 lit: syn::Lit::Str(lit_str),
                 ..
             }) => self.escaped(&lit_str.value()),
+            _ if self.options.xml => self.extend(
+                quote_spanned! {
+                    expr.span() =>
+                    ::markup::Render::render(&(#expr), &mut ::markup::XmlWriter(&mut *#writer))?;
+                },
+                writer,
+            ),
             _ => self.extend(
                 quote_spanned!(expr.span() => ::markup::Render::render(&(#expr), #writer)?;),
                 writer,
@@ -311,6 +1234,7 @@ impl Stream {
     }
 
     fn extend<Iter: IntoIterator<Item = TokenTree>>(&mut self, iter: Iter, writer: &Ident) {
+        self.trim = false;
         if !self.buffer.is_empty() {
             let buffer = &self.buffer;
             self.stream.extend(quote! {
@@ -322,10 +1246,16 @@ impl Stream {
     }
 
     fn braced(&mut self, f: impl Fn(&mut Stream), writer: &Ident) {
-        let mut stream = Stream::default();
+        let mut stream = Stream {
+            stream: TokenStream::new(),
+            buffer: String::new(),
+            options: self.options.clone(),
+            preserve: self.preserve,
+            trim: false,
+        };
         f(&mut stream);
         let stream = stream.finish(writer);
-        self.stream.extend(quote!({#stream}));
+        self.extend(quote!({#stream}), writer);
     }
 
     fn finish(mut self, writer: &Ident) -> TokenStream {
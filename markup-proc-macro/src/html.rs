@@ -0,0 +1 @@
+../../markup/src/html.rs
\ No newline at end of file
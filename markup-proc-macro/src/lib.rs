@@ -1,12 +1,16 @@
 mod ast;
+mod check;
 mod escape;
 mod generate;
+mod html;
+mod minify;
 mod parse;
+mod spec;
 
 #[proc_macro]
 pub fn define(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let structs = syn::parse_macro_input!(tokens as parse::Many<ast::Struct>).0;
-    quote::quote!( #(#structs)* ).into()
+    let define = syn::parse_macro_input!(tokens as ast::Define);
+    quote::quote!( #define ).into()
 }
 
 #[proc_macro]
@@ -14,3 +18,41 @@ pub fn new(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let new = syn::parse_macro_input!(tokens as ast::Template);
     quote::quote!( #new ).into()
 }
+
+#[proc_macro]
+pub fn include(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let include = syn::parse_macro_input!(tokens with parse::include);
+    quote::quote!( #include ).into()
+}
+
+#[proc_macro_attribute]
+pub fn component(
+    attributes: proc_macro::TokenStream,
+    tokens: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    if let Some(attribute) = proc_macro2::TokenStream::from(attributes)
+        .into_iter()
+        .next()
+    {
+        return syn::Error::new(
+            attribute.span(),
+            "`#[markup::component]` takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let component = syn::parse_macro_input!(tokens as ast::Component);
+    quote::quote!( #component ).into()
+}
+
+#[proc_macro_derive(Render, attributes(markup))]
+pub fn derive_render(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let derive = syn::parse_macro_input!(tokens as ast::Derive);
+    quote::quote!( #derive ).into()
+}
+
+#[proc_macro_derive(Props)]
+pub fn derive_props(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let props = syn::parse_macro_input!(tokens as ast::DeriveProps);
+    quote::quote!( #props ).into()
+}
@@ -0,0 +1,319 @@
+//! Minifiers for the static parts of templates using `#[markup(minify)]`.
+//!
+//! These only ever remove whitespace, comments and redundant semicolons, and leave anything they
+//! don't understand as is.
+
+use std::iter::Peekable;
+
+/// Minifies a CSS stylesheet.
+pub fn css(source: &str) -> String {
+    // Whitespace next to these is never needed.
+    const PUNCTUATION: &[char] = &['{', '}', ';', ',', '>'];
+    let mut output = String::new();
+    let mut chars = source.chars().peekable();
+    let mut space = false;
+    while let Some(c) = chars.next() {
+        if c.is_ascii_whitespace() {
+            space = true;
+            continue;
+        }
+        if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut last = '\0';
+            for c in chars.by_ref() {
+                if last == '*' && c == '/' {
+                    break;
+                }
+                last = c;
+            }
+            space = true;
+            continue;
+        }
+        if let Some(last) = output.chars().last() {
+            if space && !PUNCTUATION.contains(&last) && last != ':' && !PUNCTUATION.contains(&c) {
+                output.push(' ');
+            }
+        }
+        space = false;
+        if c == '}' && output.ends_with(';') {
+            output.pop();
+        }
+        output.push(c);
+        if c == '"' || c == '\'' {
+            string(c, &mut chars, &mut output);
+        }
+    }
+    output
+}
+
+/// Minifies a script.
+///
+/// Newlines are kept unless they're obviously not needed, so automatic semicolon insertion works
+/// the same as before.
+pub fn js(source: &str) -> String {
+    let mut output = String::new();
+    let mut chars = source.chars().peekable();
+    let mut space = None;
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            if c == '\n' || c == '\r' || space == Some('\n') {
+                space = Some('\n');
+            } else {
+                space = Some(' ');
+            }
+            continue;
+        }
+        if c == '/' && chars.peek() == Some(&'/') {
+            while matches!(chars.peek(), Some(c) if *c != '\n' && *c != '\r') {
+                chars.next();
+            }
+            space = space.or(Some(' '));
+            continue;
+        }
+        if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut last = '\0';
+            let mut newline = false;
+            for c in chars.by_ref() {
+                if last == '*' && c == '/' {
+                    break;
+                }
+                newline |= c == '\n' || c == '\r';
+                last = c;
+            }
+            if newline {
+                space = Some('\n');
+            } else {
+                space = space.or(Some(' '));
+            }
+            continue;
+        }
+        if let (Some(space), Some(last)) = (space, output.chars().last()) {
+            let word =
+                |c: char| c.is_alphanumeric() || matches!(c, '_' | '$' | '\\') || !c.is_ascii();
+            if space == '\n' {
+                if !matches!(last, '{' | ';' | ',' | '(' | '[') && !matches!(c, '}' | ')' | ']') {
+                    output.push('\n');
+                }
+            } else if (word(last) && word(c))
+                || (matches!(last, '+' | '-') && last == c)
+                || (last == '/' && matches!(c, '/' | '*'))
+            {
+                output.push(' ');
+            }
+        }
+        let regex = c == '/' && regex_allowed(&output);
+        space = None;
+        output.push(c);
+        if matches!(c, '"' | '\'') {
+            string(c, &mut chars, &mut output);
+        } else if c == '`' {
+            template(&mut chars, &mut output);
+        } else if regex {
+            // Copied as is up to the closing `/`. A regular expression can't span lines, so if
+            // this wasn't one after all the rest of the line is kept untouched.
+            let mut class = false;
+            while let Some(c) = chars.next() {
+                if c == '\n' || c == '\r' {
+                    space = Some('\n');
+                    break;
+                }
+                output.push(c);
+                match c {
+                    '\\' => output.extend(chars.next()),
+                    '[' => class = true,
+                    ']' => class = false,
+                    '/' if !class => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+    output
+}
+
+/// Whether a `/` following `output` starts a regular expression rather than being a division.
+fn regex_allowed(output: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "await",
+        "case",
+        "delete",
+        "do",
+        "else",
+        "in",
+        "instanceof",
+        "new",
+        "return",
+        "throw",
+        "typeof",
+        "void",
+        "yield",
+    ];
+    let output = output.trim_end();
+    match output.chars().last() {
+        None => true,
+        Some(c) if c.is_alphanumeric() || matches!(c, '_' | '$') => {
+            let word = output
+                .rsplit(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '$')))
+                .next()
+                .unwrap_or("");
+            KEYWORDS.contains(&word)
+        }
+        Some(c) => !matches!(c, ')' | ']' | '"' | '\'' | '`'),
+    }
+}
+
+/// Copies the rest of a string literal started by `quote`.
+fn string(quote: char, chars: &mut impl Iterator<Item = char>, output: &mut String) {
+    while let Some(c) = chars.next() {
+        output.push(c);
+        if c == '\\' {
+            output.extend(chars.next());
+        } else if c == quote {
+            break;
+        }
+    }
+}
+
+/// Copies the rest of a template literal as is, including its substitutions, which may contain
+/// strings and template literals of their own.
+fn template(chars: &mut Peekable<impl Iterator<Item = char>>, output: &mut String) {
+    while let Some(c) = chars.next() {
+        output.push(c);
+        match c {
+            '\\' => output.extend(chars.next()),
+            '`' => break,
+            '$' if chars.peek() == Some(&'{') => {
+                output.extend(chars.next());
+                substitution(chars, output);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Copies the rest of a `${...}` substitution in a template literal as is.
+fn substitution(chars: &mut Peekable<impl Iterator<Item = char>>, output: &mut String) {
+    let mut depth = 0;
+    while let Some(c) = chars.next() {
+        output.push(c);
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => break,
+            '}' => depth -= 1,
+            '"' | '\'' => string(c, chars, output),
+            '`' => template(chars, output),
+            _ => {}
+        }
+    }
+}
+
+/// What follows an element, as far as can be told at compile time.
+pub enum Next {
+    /// An element with this name.
+    Element(String),
+    /// The end of the parent element with this name.
+    End(String),
+    /// Anything else.
+    Other,
+}
+
+/// Whether the end tag of the element `name` may be omitted when it's followed by `next`, as
+/// described in <https://html.spec.whatwg.org/multipage/syntax.html#optional-tags>.
+pub fn optional_end_tag(name: &str, next: Next) -> bool {
+    const P: &[&str] = &[
+        "address",
+        "article",
+        "aside",
+        "blockquote",
+        "details",
+        "dialog",
+        "div",
+        "dl",
+        "fieldset",
+        "figcaption",
+        "figure",
+        "footer",
+        "form",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "header",
+        "hgroup",
+        "hr",
+        "main",
+        "menu",
+        "nav",
+        "ol",
+        "p",
+        "pre",
+        "search",
+        "section",
+        "table",
+        "ul",
+    ];
+    let (followers, end): (&[&str], bool) = match name {
+        "li" => (&["li"], true),
+        "dt" => (&["dt", "dd"], false),
+        "dd" => (&["dt", "dd"], true),
+        "rt" | "rp" => (&["rt", "rp"], true),
+        "optgroup" => (&["optgroup", "hr"], true),
+        "option" => (&["option", "optgroup", "hr"], true),
+        "thead" => (&["tbody", "tfoot"], false),
+        "tbody" => (&["tbody", "tfoot"], true),
+        "tfoot" => (&[], true),
+        "tr" => (&["tr"], true),
+        "td" | "th" => (&["td", "th"], true),
+        "p" => {
+            return match next {
+                Next::Element(next) => P.contains(&next.as_str()),
+                // Custom elements are excluded too as they may be transparent.
+                Next::End(parent) => {
+                    !parent.contains('-')
+                        && !["a", "audio", "del", "ins", "map", "noscript", "video"]
+                            .contains(&parent.as_str())
+                }
+                Next::Other => false,
+            };
+        }
+        _ => return false,
+    };
+    match next {
+        Next::Element(next) => followers.contains(&next.as_str()),
+        Next::End(_) => end,
+        Next::Other => false,
+    }
+}
+
+#[test]
+fn test() {
+    assert_eq!(
+        css("a  >  b , c {\n  color : red ;\n  /* x */ margin: 0 auto;\n}\n\np::before { content: \"a  ;}\" }"),
+        "a>b,c{color :red;margin:0 auto}p::before{content:\"a  ;}\"}"
+    );
+    assert_eq!(
+        css("@media screen and (min-width: 1px) {}"),
+        "@media screen and (min-width:1px){}"
+    );
+    assert_eq!(
+        js("// x\nvar a = 1 ;\nvar b = a + +1 / 2; /* y */\nif (a) {\n  f('  //  ', /  \\/ [/]/g)\n}\nreturn\nx\n"),
+        "var a=1;var b=a+ +1/2;if(a){f('  //  ',/  \\/ [/]/g)}\nreturn\nx"
+    );
+    assert_eq!(js("a = b\n(c)"), "a=b\n(c)");
+    assert_eq!(js("x = `a  ${ b }`"), "x=`a  ${ b }`");
+    assert_eq!(
+        js("x = `a  ${ `b  ${ { c: '}' }.c }  d` }  e` ; y = 1"),
+        "x=`a  ${ `b  ${ { c: '}' }.c }  d` }  e`;y=1"
+    );
+    assert!(optional_end_tag("li", Next::Element("li".into())));
+    assert!(optional_end_tag("li", Next::End("ul".into())));
+    assert!(!optional_end_tag("li", Next::Other));
+    assert!(!optional_end_tag("dt", Next::End("dl".into())));
+    assert!(optional_end_tag("p", Next::Element("div".into())));
+    assert!(!optional_end_tag("p", Next::Element("span".into())));
+    assert!(!optional_end_tag("p", Next::End("a".into())));
+    assert!(!optional_end_tag("div", Next::End("body".into())));
+}
@@ -1,43 +1,154 @@
 use crate::ast::{
-    Attribute, Element, For, If, IfClause, IfClauseTest, Match, MatchClause, Node, Struct, Template,
+    Attribute, Component, Define, Definition, Derive, DeriveProps, Element, Entity, Enum, For, If,
+    IfClause, IfClauseTest, Level, Lints, Match, MatchClause, Node, Options, PendingProps, Props,
+    Struct, Template, Variant, WrapIf,
 };
+use crate::check::{suggest, GROUPS, LINTS};
+use crate::generate::literal;
+use crate::spec::ENTITIES;
+use syn::parse::discouraged::Speculative;
 use syn::parse::{Parse, ParseStream, Result};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{LitBool, LitStr};
 
+impl Parse for Define {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // The fields of the `..name: Type` fields without a list, which the macros defined by
+        // `#[derive(markup::Props)]` pass back as `@fields [[a, b] [c]]`.
+        let mut fields = Vec::new();
+        if input.peek(syn::Token![@]) {
+            let _: syn::Token![@] = input.parse()?;
+            let keyword: syn::Ident = input.parse()?;
+            if keyword != "fields" {
+                return Err(syn::Error::new(keyword.span(), "expected `fields`"));
+            }
+            let outer;
+            syn::bracketed!(outer in input);
+            while !outer.is_empty() {
+                let inner;
+                syn::bracketed!(inner in outer);
+                fields.push(
+                    Punctuated::<syn::Ident, syn::token::Comma>::parse_terminated(&inner)?
+                        .into_iter()
+                        .collect::<Vec<_>>(),
+                );
+            }
+        }
+        let tokens = input.fork().parse::<proc_macro2::TokenStream>()?;
+        let mut definitions = input.parse::<Many<Definition>>()?.0;
+        let mut known = fields.iter();
+        let mut pending = None;
+        let props = definitions
+            .iter_mut()
+            .flat_map(|definition| match definition {
+                Definition::Struct(Struct {
+                    props,
+                    generics,
+                    where_clause,
+                    ..
+                }) => {
+                    let (generics, where_clause) = (&*generics, &*where_clause);
+                    props
+                        .iter_mut()
+                        .map(move |props| (props, generics, where_clause))
+                        .collect()
+                }
+                Definition::Enum(Enum {
+                    variants,
+                    generics,
+                    where_clause,
+                    ..
+                }) => {
+                    let (generics, where_clause) = (&*generics, &*where_clause);
+                    variants
+                        .iter_mut()
+                        .flat_map(|variant| variant.props.iter_mut())
+                        .map(move |props| (props, generics, where_clause))
+                        .collect::<Vec<_>>()
+                }
+            })
+            .filter(|(props, _, _)| !props.listed);
+        for (props, generics, where_clause) in props {
+            match known.next() {
+                Some(fields) => {
+                    // Bind the fields where the template can see them.
+                    props.fields = fields
+                        .iter()
+                        .map(|field| {
+                            let mut field = field.clone();
+                            field.set_span(props.name.span());
+                            field
+                        })
+                        .collect();
+                }
+                None => {
+                    pending = Some(PendingProps {
+                        path: props.path.clone(),
+                        ty: props.ty.clone(),
+                        generics: generics.clone(),
+                        where_clause: where_clause.clone(),
+                        fields,
+                        tokens,
+                    });
+                    break;
+                }
+            }
+        }
+        Ok(Define {
+            definitions,
+            pending,
+        })
+    }
+}
+
+impl Parse for Definition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let fork = input.fork();
+        fork.call(syn::Attribute::parse_outer)?;
+        if fork.peek(syn::Token![enum]) {
+            Ok(Definition::Enum(input.parse()?))
+        } else {
+            Ok(Definition::Struct(input.parse()?))
+        }
+    }
+}
+
 impl Parse for Struct {
     fn parse(input: ParseStream) -> Result<Self> {
         let start_input_len = input.to_string().len();
-        let attributes = input.call(syn::Attribute::parse_outer)?;
+        let mut attributes = input.call(syn::Attribute::parse_outer)?;
+        let options = options(&mut attributes)?;
         let name = input.parse()?;
         let generics = input.parse()?;
-        let fields = {
-            if input.peek(syn::token::Paren) {
-                let fields;
-                syn::parenthesized!(fields in input);
-                Punctuated::<syn::Field, syn::token::Comma>::parse_terminated_with(
-                    &fields,
-                    syn::Field::parse_named,
-                )?
-                .into_pairs()
-                .map(|pair| pair.into_value())
-                .collect()
-            } else {
-                Vec::new()
-            }
-        };
+        let (fields, props) = fields(input)?;
         let where_clause = if input.peek(syn::token::Where) {
             Some(input.parse()?)
         } else {
             None
         };
-        let mut children = Vec::new();
+        if peek_include(input) {
+            let File {
+                path,
+                children,
+                size,
+            } = file(&include_path(input)?)?;
+            return Ok(Struct {
+                name,
+                attributes,
+                generics,
+                where_clause,
+                fields,
+                props,
+                children,
+                size_hint: size,
+                include: Some(path),
+                options,
+            });
+        }
         let inner;
         syn::braced!(inner in input);
-        while !inner.is_empty() {
-            children.push(inner.parse()?);
-        }
+        let children = inner.parse::<Many<_>>()?.0;
         // We use the length of the tokens that define this template as a rough estimate of the
         // number of bytes the output of this template will occupy.
         // Lifted from Maud [1].
@@ -49,17 +160,624 @@ impl Parse for Struct {
             generics,
             where_clause,
             fields,
+            props,
             children,
             size_hint,
+            include: None,
+            options,
+        })
+    }
+}
+
+impl Parse for Enum {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attributes = input.call(syn::Attribute::parse_outer)?;
+        let options = options(&mut attributes)?;
+        let _: syn::Token![enum] = input.parse()?;
+        let name = input.parse()?;
+        let generics = input.parse()?;
+        let where_clause = if input.peek(syn::token::Where) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let inner;
+        syn::braced!(inner in input);
+        let variants = inner.parse::<Many<_>>()?.0;
+        Ok(Enum {
+            name,
+            attributes,
+            generics,
+            where_clause,
+            variants,
+            options,
+        })
+    }
+}
+
+impl Parse for Variant {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let start_input_len = input.to_string().len();
+        let attributes = input.call(syn::Attribute::parse_outer)?;
+        let name = input.parse()?;
+        let (fields, props) = fields(input)?;
+        let inner;
+        syn::braced!(inner in input);
+        let children = inner.parse::<Many<_>>()?.0;
+        // Same estimate as in `Struct`, but per variant.
+        let size_hint = start_input_len - input.to_string().len();
+        Ok(Variant {
+            name,
+            attributes,
+            fields,
+            props,
+            children,
+            size_hint,
+        })
+    }
+}
+
+impl Parse for Derive {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let syn::DeriveInput {
+            mut attrs,
+            ident: name,
+            generics,
+            data,
+            ..
+        } = input.parse()?;
+        let fields = match data {
+            syn::Data::Struct(syn::DataStruct {
+                fields: syn::Fields::Named(fields),
+                ..
+            }) => fields
+                .named
+                .into_iter()
+                .map(|field| field.ident.unwrap())
+                .collect(),
+            syn::Data::Struct(syn::DataStruct {
+                fields: syn::Fields::Unit,
+                ..
+            }) => Vec::new(),
+            _ => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`markup::Render` can only be derived for structs with named fields",
+                ))
+            }
+        };
+        let options = options(&mut attrs)?;
+        let mut template = attrs.iter().filter(|attr| attr.path().is_ident("markup"));
+        let children = match (template.next(), template.next()) {
+            (Some(attr), None) => {
+                attr.parse_args_with(|input: ParseStream| input.parse::<Many<_>>())?
+                    .0
+            }
+            (_, Some(attr)) => {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "duplicate `#[markup { ... }]` attribute",
+                ))
+            }
+            (None, None) => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "missing `#[markup { ... }]` attribute containing the template",
+                ))
+            }
+        };
+        Ok(Derive {
+            name,
+            generics,
+            fields,
+            children,
+            options,
+        })
+    }
+}
+
+impl Parse for DeriveProps {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let syn::DeriveInput {
+            ident: name,
+            generics,
+            data,
+            ..
+        } = input.parse()?;
+        let fields = match data {
+            syn::Data::Struct(syn::DataStruct {
+                fields: syn::Fields::Named(fields),
+                ..
+            }) => fields
+                .named
+                .into_iter()
+                .map(|field| field.ident.unwrap())
+                .collect(),
+            _ => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`markup::Props` can only be derived for structs with named fields",
+                ))
+            }
+        };
+        Ok(DeriveProps {
+            name,
+            generics,
+            fields,
+        })
+    }
+}
+
+impl Parse for Component {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let syn::ItemFn {
+            attrs: mut attributes,
+            vis: visibility,
+            sig: signature,
+            block,
+        } = input.parse()?;
+        let options = options(&mut attributes)?;
+        let span = block.span();
+        let mut statements = block.stmts;
+        // The body must be valid Rust for the attribute to be applied at all, so the template goes
+        // in a `markup::new!` invocation at the end of it.
+        let mac = match statements.pop() {
+            Some(syn::Stmt::Macro(syn::StmtMacro { mac, .. }))
+            | Some(syn::Stmt::Expr(syn::Expr::Macro(syn::ExprMacro { mac, .. }), None))
+                if matches!(mac.path.segments.last(), Some(segment) if segment.ident == "new") =>
+            {
+                mac
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    "expected the function body to end with a `markup::new! { ... }` template",
+                ))
+            }
+        };
+        let children = mac.parse_body::<Many<_>>()?.0;
+        Ok(Component {
+            attributes,
+            visibility,
+            signature,
+            statements,
+            children,
+            options,
         })
     }
 }
 
 impl Parse for Template {
     fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(syn::Token![|]) || input.peek(syn::Token![||]) {
+            let parameters = parameters(input)?;
+            let inner;
+            syn::braced!(inner in input);
+            let children = inner.parse::<Many<Node>>()?.0;
+            if !input.is_empty() {
+                return Err(input.error("unexpected tokens after the template body"));
+            }
+            return Ok(Self {
+                parameters: Some(parameters),
+                children,
+                include: None,
+            });
+        }
         let children = input.parse::<Many<Node>>()?.0;
-        Ok(Self { children })
+        Ok(Self {
+            parameters: None,
+            children,
+            include: None,
+        })
+    }
+}
+
+/// Removes the `#[markup(...)]` attributes from `attributes` and returns the options they set.
+/// `#[markup { ... }]` attributes, which hold derived templates, are left alone.
+fn options(attributes: &mut Vec<syn::Attribute>) -> Result<Options> {
+    let mut options = Options::default();
+    let is_options = |attribute: &syn::Attribute| {
+        attribute.path().is_ident("markup")
+            && matches!(
+                &attribute.meta,
+                syn::Meta::List(list) if matches!(list.delimiter, syn::MacroDelimiter::Paren(_))
+            )
+    };
+    for attribute in attributes.iter().filter(|attribute| is_options(attribute)) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("minify") {
+                options.minify = true;
+            } else if meta.path.is_ident("omit_optional_tags") {
+                options.omit_optional_tags = true;
+            } else if meta.path.is_ident("xml") {
+                options.xml = true;
+            } else if meta.path.is_ident("attribute_prefixes") {
+                let prefixes;
+                syn::parenthesized!(prefixes in meta.input);
+                let prefixes = Punctuated::<LitStr, syn::Token![,]>::parse_terminated(&prefixes)?;
+                options
+                    .attribute_prefixes
+                    .extend(prefixes.iter().map(|prefix| prefix.value()));
+            } else if !lint_levels(&meta, &mut options.lints)? {
+                return Err(meta.error(
+                    "unknown option, expected `minify`, `omit_optional_tags`, `xml`, `attribute_prefixes`, `allow`, `warn` or `deny`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    attributes.retain(|attribute| !is_options(attribute));
+    Ok(options)
+}
+
+/// Parses `allow(...)`, `warn(...)` or `deny(...)` into `lints`, returning whether `meta` was one
+/// of them.
+fn lint_levels(meta: &syn::meta::ParseNestedMeta, lints: &mut Lints) -> Result<bool> {
+    let level = if meta.path.is_ident("allow") {
+        Level::Allow
+    } else if meta.path.is_ident("warn") {
+        Level::Warn
+    } else if meta.path.is_ident("deny") {
+        Level::Deny
+    } else {
+        return Ok(false);
+    };
+    meta.parse_nested_meta(|meta| {
+        let lint = meta.path.require_ident()?;
+        let name = lint.to_string();
+        if !LINTS.contains(&name.as_str()) && !GROUPS.iter().any(|(group, _)| *group == name) {
+            return Err(meta.error(format!("unknown check `{}`", lint)));
+        }
+        lints.0.push((lint.clone(), level));
+        Ok(())
+    })?;
+    Ok(true)
+}
+
+fn parameters(input: ParseStream) -> Result<Vec<syn::PatType>> {
+    let mut parameters = Vec::new();
+    if input.peek(syn::Token![||]) {
+        let _: syn::Token![||] = input.parse()?;
+        return Ok(parameters);
+    }
+    let _: syn::Token![|] = input.parse()?;
+    while !input.peek(syn::Token![|]) {
+        let pat = syn::Pat::parse_single(input)?;
+        if !input.peek(syn::Token![:]) {
+            return Err(syn::Error::new(
+                pat.span(),
+                "template parameters must have a type, e.g. `|item: &Item|`",
+            ));
+        }
+        parameters.push(syn::PatType {
+            attrs: Vec::new(),
+            pat: Box::new(pat),
+            colon_token: input.parse()?,
+            ty: input.parse()?,
+        });
+        if input.peek(syn::Token![|]) {
+            break;
+        }
+        let _: syn::Token![,] = input.parse()?;
+    }
+    let _: syn::Token![|] = input.parse()?;
+    Ok(parameters)
+}
+
+pub fn include(input: ParseStream) -> Result<Template> {
+    let File { path, children, .. } = file(&input.parse()?)?;
+    Ok(Template {
+        parameters: None,
+        children,
+        include: Some(path),
+    })
+}
+
+struct File {
+    path: String,
+    children: Vec<Node>,
+    size: usize,
+}
+
+fn peek_include(input: ParseStream) -> bool {
+    let fork = input.fork();
+    matches!(fork.parse::<syn::Ident>(), Ok(ident) if ident == "include")
+        && fork.peek(syn::Token![!])
+}
+
+fn include_path(input: ParseStream) -> Result<LitStr> {
+    let _: syn::Ident = input.parse()?;
+    let _: syn::Token![!] = input.parse()?;
+    let inner;
+    syn::parenthesized!(inner in input);
+    let path = inner.parse()?;
+    if !inner.is_empty() {
+        return Err(inner.error("expected a single string literal"));
     }
+    Ok(path)
+}
+
+/// Parses the template in the file at `lit`, resolved relative to the directory containing the
+/// crate's `Cargo.toml`, like `std::include_str!` would from the crate root.
+fn file(lit: &LitStr) -> Result<File> {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+    let path = std::path::Path::new(&root).join(lit.value());
+    let source = std::fs::read_to_string(&path).map_err(|error| {
+        syn::Error::new(
+            lit.span(),
+            format!("couldn't read `{}`: {}", lit.value(), error),
+        )
+    })?;
+    // Errors name the file as it's written in the template.
+    let error = |offset: Option<usize>, message: String| {
+        let (line, column) = offset.map_or((1, 1), |offset| position(&source, offset));
+        syn::Error::new(
+            lit.span(),
+            format!("{}:{}:{}: {}", lit.value(), line, column, message),
+        )
+    };
+    let tokens = source
+        .parse::<proc_macro2::TokenStream>()
+        .map_err(|lex_error| error(lex_error_offset(&source), lex_error.to_string()))?;
+    let children = parse_nodes(respan(tokens.clone(), lit.span()))
+        .map_err(|parse_error| {
+            let message = parse_error.to_string();
+            error(parse_error_offset(&source, &tokens, &message), message)
+        })?
+        .0;
+    Ok(File {
+        path: path.display().to_string(),
+        children,
+        size: source.len(),
+    })
+}
+
+fn parse_nodes(tokens: proc_macro2::TokenStream) -> Result<Many<Node>> {
+    syn::parse::Parser::parse2(|input: ParseStream| input.parse::<Many<Node>>(), tokens)
+}
+
+/// Returns the line and column of the byte `offset` in `source`, both starting at 1.
+fn position(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Finds the offset of the first parse error in `source`, whose tokens are `tokens`.
+///
+/// Tokens created by a procedural macro from a string all point at the macro's call site, so the
+/// error can't be located from its span. Instead we look for the shortest prefix of the tokens
+/// which fails with the same `message`, whose last token is where the error is.
+fn parse_error_offset(
+    source: &str,
+    tokens: &proc_macro2::TokenStream,
+    message: &str,
+) -> Option<usize> {
+    let mut offsets = Vec::new();
+    token_offsets(source, tokens.clone(), 0, &mut offsets)?;
+    let fails = |mut count: usize| match parse_nodes(prefix(tokens.clone(), &mut count)) {
+        Ok(_) => false,
+        Err(error) => error.to_string() == message,
+    };
+    let (mut low, mut high) = (1, offsets.len());
+    while low < high {
+        let middle = (low + high) / 2;
+        if fails(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+    let (start, end) = *offsets.get(low.checked_sub(1)?)?;
+    // Errors about a missing token are at whatever comes after the last one.
+    if message.starts_with("unexpected end of input") {
+        Some(skip_trivia(source, end))
+    } else {
+        Some(start)
+    }
+}
+
+/// Finds where each token in `tokens`, and each opening delimiter, starts and ends in `source`,
+/// beginning at `offset`. Returns the offset after the tokens, or `None` if they can't be found,
+/// like for doc comments, which are turned into attributes.
+fn token_offsets(
+    source: &str,
+    tokens: proc_macro2::TokenStream,
+    mut offset: usize,
+    offsets: &mut Vec<(usize, usize)>,
+) -> Option<usize> {
+    use proc_macro2::{Delimiter, TokenTree};
+    let expect = |offset: usize, text: &str| {
+        let offset = skip_trivia(source, offset);
+        source[offset..]
+            .starts_with(text)
+            .then(|| (offset, offset + text.len()))
+    };
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                let (start, end) = expect(offset, open)?;
+                offsets.push((start, end));
+                let inner = token_offsets(source, group.stream(), end, offsets)?;
+                offset = expect(inner, close)?.1;
+            }
+            token => {
+                let (start, end) = expect(offset, &token.to_string())?;
+                offsets.push((start, end));
+                offset = end;
+            }
+        }
+    }
+    Some(offset)
+}
+
+/// Returns the first `count` tokens of `tokens`, counting opening delimiters as tokens and
+/// closing the groups which are cut off.
+fn prefix(tokens: proc_macro2::TokenStream, count: &mut usize) -> proc_macro2::TokenStream {
+    use proc_macro2::{Group, TokenTree};
+    let mut prefix = proc_macro2::TokenStream::new();
+    for token in tokens {
+        if *count == 0 {
+            break;
+        }
+        *count -= 1;
+        prefix.extend(Some(match token {
+            TokenTree::Group(group) => {
+                let mut cut = Group::new(group.delimiter(), self::prefix(group.stream(), count));
+                cut.set_span(group.span());
+                TokenTree::Group(cut)
+            }
+            token => token,
+        }));
+    }
+    prefix
+}
+
+/// Skips whitespace and comments in `source` from `offset`.
+fn skip_trivia(source: &str, mut offset: usize) -> usize {
+    loop {
+        let rest = &source[offset..];
+        let trimmed = rest.trim_start();
+        offset += rest.len() - trimmed.len();
+        if trimmed.starts_with("//") {
+            offset += trimmed.find('\n').unwrap_or(trimmed.len());
+        } else if trimmed.starts_with("/*") {
+            offset += block_comment(trimmed).unwrap_or(trimmed.len());
+        } else {
+            return offset;
+        }
+    }
+}
+
+/// Returns the length of the possibly nested block comment `source` starts with, if it's closed.
+fn block_comment(source: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut index = 0;
+    while index < source.len() {
+        if source[index..].starts_with("/*") {
+            depth += 1;
+            index += 2;
+        } else if source[index..].starts_with("*/") {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                return Some(index);
+            }
+        } else {
+            index += source[index..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    None
+}
+
+/// Finds the offset of the first unbalanced delimiter or unterminated literal or comment in
+/// `source`, which is what most lex errors are about.
+fn lex_error_offset(source: &str) -> Option<usize> {
+    let mut open = Vec::new();
+    let mut offset = 0;
+    while offset < source.len() {
+        let rest = &source[offset..];
+        let next = skip_trivia(source, offset);
+        if next != offset {
+            offset = next;
+            continue;
+        }
+        if rest.starts_with("/*") {
+            // An unterminated comment, which `skip_trivia` couldn't skip.
+            return Some(offset);
+        }
+        let c = rest.chars().next()?;
+        if c.is_alphanumeric() || c == '_' {
+            let word = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .map_or(rest, |length| &rest[..length]);
+            offset += word.len();
+            let hashes = source[offset..].len() - source[offset..].trim_start_matches('#').len();
+            if matches!(word, "r" | "br") && source[offset + hashes..].starts_with('"') {
+                // A raw string.
+                let end = format!("\"{}", "#".repeat(hashes));
+                match source[offset + hashes + 1..].find(&end) {
+                    Some(length) => offset += hashes + 1 + length + end.len(),
+                    None => return Some(offset - word.len()),
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' => match string_end(&rest[1..], '"') {
+                Some(length) => offset += 1 + length,
+                None => return Some(offset),
+            },
+            '\'' => {
+                // A character literal, or the start of a lifetime.
+                let mut chars = rest[1..].chars();
+                match (chars.next(), chars.next()) {
+                    (Some('\\'), _) => match string_end(&rest[1..], '\'') {
+                        Some(length) => offset += 1 + length,
+                        None => return Some(offset),
+                    },
+                    (Some(c), Some('\'')) => offset += 2 + c.len_utf8(),
+                    _ => offset += 1,
+                }
+            }
+            '(' | '[' | '{' => {
+                open.push((c, offset));
+                offset += 1;
+            }
+            ')' | ']' | '}' => {
+                match open.pop() {
+                    Some(('(', _)) if c == ')' => {}
+                    Some(('[', _)) if c == ']' => {}
+                    Some(('{', _)) if c == '}' => {}
+                    _ => return Some(offset),
+                }
+                offset += 1;
+            }
+            _ => offset += c.len_utf8(),
+        }
+    }
+    open.last().map(|&(_, offset)| offset)
+}
+
+/// Returns the length of `source` up to and including the first unescaped `quote`.
+fn string_end(source: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in source.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+fn respan(tokens: proc_macro2::TokenStream, span: proc_macro2::Span) -> proc_macro2::TokenStream {
+    use proc_macro2::{Group, TokenTree};
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
 }
 
 impl Parse for Node {
@@ -83,6 +801,22 @@ impl Parse for Node {
             } else if lookahead.peek(syn::token::Match) {
                 let _: syn::token::Match = input.parse()?;
                 Ok(Node::Match(input.parse()?))
+            } else if peek_wrap_if(input) {
+                let _: syn::Ident = input.parse()?;
+                Ok(Node::WrapIf(input.parse()?))
+            } else if lookahead.peek(syn::Token![$]) {
+                let _: syn::Token![$] = input.parse()?;
+                Ok(Node::Interpolated(interpolate(&input.parse()?)?))
+            } else if input.peek(syn::token::Brace) && peek_format(input) {
+                let inner;
+                syn::braced!(inner in input);
+                let expr = inner.parse()?;
+                let colon: syn::Token![:] = inner.parse()?;
+                let mut spec = String::new();
+                while !inner.is_empty() {
+                    spec.push_str(&inner.parse::<proc_macro2::TokenTree>()?.to_string());
+                }
+                Ok(Node::Expr(format_args(expr, &spec, colon.span)))
             } else if lookahead.peek(syn::Lit)
                 || lookahead.peek(syn::Ident)
                 || lookahead.peek(syn::token::Brace)
@@ -91,25 +825,348 @@ impl Parse for Node {
                 || lookahead.peek(syn::token::SelfType)
                 || lookahead.peek(syn::token::Super)
             {
-                Ok(Node::Expr(input.parse()?))
+                let expr = input.parse()?;
+                if input.peek(syn::Token![:]) && !input.peek(syn::Token![::]) {
+                    let colon: syn::Token![:] = input.parse()?;
+                    let spec = format_spec(input)?;
+                    return Ok(Node::Expr(format_args(expr, &spec, colon.span)));
+                }
+                Ok(Node::Expr(expr))
             } else if input.fork().parse::<syn::Stmt>().is_ok() {
                 Ok(Node::Stmt(input.parse()?))
             } else {
                 Err(lookahead.error())
             }
+        } else if lookahead.peek(syn::Token![&]) {
+            Ok(Node::Entity(input.parse()?))
         } else if lookahead.peek(syn::Lit) {
             let lit: syn::Lit = input.parse()?;
             Ok(Node::Expr(syn::parse_quote_spanned!(lit.span() => #lit)))
         } else if lookahead.peek(syn::token::Brace) {
             Ok(Node::Expr(input.parse()?))
+        } else if input.fork().parse::<syn::Expr>().is_ok() {
+            Err(input.error(
+                "expected an element, a string literal or `@`, put `@` before expressions to render them",
+            ))
         } else {
             Err(lookahead.error())
         }
     }
 }
 
+/// Whether `input` starts with `wrap_if` and a condition, rather than an expression using a
+/// variable or function named `wrap_if`.
+fn peek_wrap_if(input: ParseStream) -> bool {
+    use syn::ext::IdentExt;
+    let fork = input.fork();
+    matches!(fork.parse::<syn::Ident>(), Ok(ident) if ident == "wrap_if")
+        && ((fork.peek(syn::Ident::peek_any) && !fork.peek(syn::Token![as]))
+            || (fork.peek(syn::Token![!])
+                && !fork.peek2(syn::token::Paren)
+                && !fork.peek2(syn::token::Bracket)
+                && !fork.peek2(syn::token::Brace))
+            || fork.peek(syn::Token![*])
+            || fork.peek(syn::Token![&])
+            || fork.peek(syn::LitBool))
+}
+
+impl Parse for WrapIf {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let test = input.parse()?;
+        // Classes without an element name, like in `@wrap_if cond .card { ... }`, are read as a
+        // field of the condition.
+        let (IfClauseTest::Expr(expr) | IfClauseTest::Let(_, expr)) = &test;
+        if let (Some(class), true) = (trailing_field(expr), input.peek(syn::token::Brace)) {
+            return Err(syn::Error::new(
+                class.span(),
+                format!("`.{class}` is read as a field of the condition of `@wrap_if`, write the element's name before its classes, like `div.{class}`"),
+            ));
+        }
+        let element: Element = input.parse()?;
+        if !element.close {
+            return Err(syn::Error::new(
+                element.name.span(),
+                "the element of `@wrap_if` needs braces around its children",
+            ));
+        }
+        Ok(WrapIf { test, element })
+    }
+}
+
+/// Returns the name of the field accessed at the end of `expr`, like `card` in `*wrap.card`.
+fn trailing_field(expr: &syn::Expr) -> Option<&syn::Ident> {
+    match expr {
+        syn::Expr::Field(syn::ExprField {
+            member: syn::Member::Named(name),
+            ..
+        }) => Some(name),
+        syn::Expr::Unary(syn::ExprUnary { expr, .. })
+        | syn::Expr::Reference(syn::ExprReference { expr, .. })
+        | syn::Expr::Binary(syn::ExprBinary { right: expr, .. }) => trailing_field(expr),
+        _ => None,
+    }
+}
+
+/// Splits the text of `@$"..."` into string literals and the variables captured by `{name}` or
+/// `{name:spec}`, with `{{` and `}}` standing for `{` and `}`.
+fn interpolate(lit: &LitStr) -> Result<Vec<syn::Expr>> {
+    let span = lit.span();
+    let error = |message: &str| Err(syn::Error::new(span, message));
+    let value = lit.value();
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '}' => return error("unmatched `}` in interpolated text, write `}}` to render `}`"),
+            '{' => {
+                let mut capture = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => capture.push(c),
+                        None => {
+                            return error(
+                                "unclosed `{` in interpolated text, write `{{` to render `{`",
+                            )
+                        }
+                    }
+                }
+                let (name, spec) = match capture.split_once(':') {
+                    Some((name, spec)) => (name, Some(spec)),
+                    None => (capture.as_str(), None),
+                };
+                let name = match syn::parse_str::<syn::Ident>(name.trim()) {
+                    Ok(mut name) => {
+                        name.set_span(span);
+                        name
+                    }
+                    Err(_) => {
+                        return error(&format!(
+                            "only variables can be captured in interpolated text, found `{{{capture}}}`, use `@` to render other expressions"
+                        ))
+                    }
+                };
+                if !text.is_empty() {
+                    let text = LitStr::new(&std::mem::take(&mut text), span);
+                    parts.push(syn::parse_quote_spanned!(span => #text));
+                }
+                let expr = syn::parse_quote_spanned!(span => #name);
+                parts.push(match spec {
+                    Some(spec) if format_spec_prefix(spec) == Some(true) => {
+                        format_args(expr, spec, span)
+                    }
+                    Some(spec) => return error(&format!("invalid format spec `{spec}`")),
+                    None => expr,
+                });
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        let text = LitStr::new(&text, span);
+        parts.push(syn::parse_quote_spanned!(span => #text));
+    }
+    Ok(parts)
+}
+
+impl Parse for Entity {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _: syn::Token![&] = input.parse()?;
+        let (reference, value) = if input.peek(syn::Token![#]) {
+            let _: syn::Token![#] = input.parse()?;
+            let token: proc_macro2::TokenTree = input.parse()?;
+            let digits = token.to_string();
+            let code = match digits.strip_prefix(['x', 'X']) {
+                Some(hex) if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                    u32::from_str_radix(hex, 16).ok()
+                }
+                _ if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => {
+                    digits.parse().ok()
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        token.span(),
+                        "expected a decimal number or `x` and a hexadecimal number",
+                    ))
+                }
+            };
+            match code.and_then(char::from_u32) {
+                Some(c) if !c.is_control() || matches!(c, '\t' | '\n' | '\u{c}') => {
+                    (format!("&#{digits};"), c.to_string())
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        token.span(),
+                        format!("`&#{digits};` isn't a valid character reference"),
+                    ))
+                }
+            }
+        } else {
+            use syn::ext::IdentExt;
+            // Names like `in` are keywords in Rust.
+            let ident = syn::Ident::parse_any(input)?;
+            let name = ident.unraw().to_string();
+            let reference = format!("&{name};");
+            match ENTITIES.binary_search_by_key(&name.as_str(), |(name, _)| name) {
+                Ok(index) => (reference, ENTITIES[index].1.to_string()),
+                Err(_) => {
+                    let mut message = format!("unknown character reference `{reference}`");
+                    // Names differing only in case are suggested first, as `suggest` would take
+                    // them as too far off.
+                    let suggestion = ENTITIES
+                        .iter()
+                        .map(|(other, _)| *other)
+                        .find(|other| other.eq_ignore_ascii_case(&name))
+                        .or_else(|| suggest(&name, ENTITIES.iter().map(|(other, _)| *other)));
+                    if let Some(suggestion) = suggestion {
+                        message.push_str(&format!(", did you mean `&{suggestion};`?"));
+                    }
+                    return Err(syn::Error::new(ident.span(), message));
+                }
+            }
+        };
+        if !input.peek(syn::Token![;]) {
+            return Err(input.error("expected `;` to end the character reference"));
+        }
+        let _: syn::Token![;] = input.parse()?;
+        Ok(Entity { reference, value })
+    }
+}
+
+/// Whether `input` starts with `{expr:spec}`.
+fn peek_format(input: ParseStream) -> bool {
+    let fork = input.fork();
+    let peek = || -> Result<bool> {
+        let inner;
+        syn::braced!(inner in fork);
+        inner.parse::<syn::Expr>()?;
+        Ok(inner.peek(syn::Token![:]) && !inner.peek(syn::Token![::]))
+    };
+    matches!(peek(), Ok(true))
+}
+
+/// Parses the format spec after `@expr:`. Without braces around it, the spec is the longest run
+/// of tokens which forms a valid one.
+fn format_spec(input: ParseStream) -> Result<String> {
+    let fork = input.fork();
+    let mut spec = String::new();
+    let mut end = None;
+    loop {
+        // A complete spec like `>4` can be followed by an element like `p { ... }`, whose name
+        // would otherwise be taken as the spec's type, as in `>4p`.
+        if !spec.is_empty()
+            && format_spec_prefix(&spec) == Some(true)
+            && (fork.peek(syn::LitStr) || fork.peek(syn::Ident) && peek_node_after_name(&fork))
+        {
+            break;
+        }
+        let token = match fork.parse::<proc_macro2::TokenTree>() {
+            Ok(token) => token,
+            Err(_) => break,
+        };
+        spec.push_str(&token.to_string());
+        match format_spec_prefix(&spec) {
+            Some(true) => end = Some((fork.fork(), spec.len())),
+            Some(false) => {}
+            None => break,
+        }
+    }
+    match end {
+        Some((fork, len)) => {
+            input.advance_to(&fork);
+            spec.truncate(len);
+            Ok(spec)
+        }
+        None => Err(input.error("expected a format spec like `.2` or `>8` after `:`")),
+    }
+}
+
+/// Whether the identifier `input` starts with is the name of an element, as it's followed by its
+/// attributes, id, classes or children, or by the rest of a dashed or namespaced name.
+fn peek_node_after_name(input: ParseStream) -> bool {
+    input.peek2(syn::token::Brace)
+        || input.peek2(syn::token::Bracket)
+        || input.peek2(syn::LitStr)
+        || input.peek2(syn::Token![;])
+        || input.peek2(syn::Token![#])
+        || input.peek2(syn::Token![.])
+        || input.peek2(syn::Token![-])
+        || input.peek2(syn::Token![:])
+}
+
+/// Whether `spec` is a complete format spec, like `>8.2` or `*^8`, or only the start of one, like
+/// `>8.` or the fill character `*`. Returns `None` if it's neither. Any character is taken as a
+/// fill character when it's followed by an alignment.
+fn format_spec_prefix(spec: &str) -> Option<bool> {
+    let mut chars = spec.chars().peekable();
+    let mut next_if = |f: &dyn Fn(char) -> bool| chars.next_if(|c| f(*c)).is_some();
+    let align = |c| matches!(c, '<' | '^' | '>');
+    if matches!(spec.chars().nth(1), Some(c) if align(c)) {
+        next_if(&|_| true);
+    }
+    next_if(&align);
+    next_if(&|c| matches!(c, '+' | '-'));
+    next_if(&|c| c == '#');
+    while next_if(&|c| c.is_ascii_digit()) {}
+    let mut complete = true;
+    if next_if(&|c| c == '.') {
+        complete = false;
+        while next_if(&|c| c.is_ascii_digit()) {
+            complete = true;
+        }
+    }
+    if complete && next_if(&|c| matches!(c, 'x' | 'X')) {
+        next_if(&|c| c == '?');
+    } else if complete {
+        next_if(&|c| matches!(c, 'o' | 'b' | 'e' | 'E' | 'p' | '?'));
+    }
+    match chars.next() {
+        // Only a fill character so far, which needs an alignment after it.
+        Some(_) if spec.chars().count() == 1 => Some(false),
+        Some(_) => None,
+        None => Some(complete),
+    }
+}
+
+/// Returns `format_args!("{:spec}", expr)`, which is rendered escaped without being written to an
+/// intermediate `String`.
+fn format_args(expr: syn::Expr, spec: &str, span: proc_macro2::Span) -> syn::Expr {
+    let format = LitStr::new(&format!("{{:{spec}}}"), span);
+    syn::parse_quote_spanned!(span => ::std::format_args!(#format, #expr))
+}
+
 impl Parse for Element {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut lints = Lints::default();
+        if input.peek(syn::Token![#]) && input.peek2(syn::token::Bracket) {
+            for attribute in input.call(syn::Attribute::parse_outer)? {
+                if !attribute.path().is_ident("markup") {
+                    return Err(syn::Error::new(
+                        attribute.span(),
+                        "only `#[markup(...)]` attributes can be put on elements",
+                    ));
+                }
+                attribute.parse_nested_meta(|meta| {
+                    if lint_levels(&meta, &mut lints)? {
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `allow`, `warn` or `deny`"))
+                    }
+                })?;
+            }
+        }
+
+        // Whether the element is just an identifier so far.
+        let mut bare = false;
         let (name, mut id, mut classes) = {
             let lookahead = input.lookahead1();
             if lookahead.peek(syn::Token![$]) {
@@ -117,6 +1174,7 @@ impl Parse for Element {
                 let name = identifier_or_string_literal_or_expression(input)?;
                 (name, None, Vec::new())
             } else if lookahead.peek(syn::Ident) {
+                bare = true;
                 let name = identifier_or_string_literal_or_expression(input)?;
                 (name, None, Vec::new())
             } else if lookahead.peek(syn::Token![#]) {
@@ -150,10 +1208,12 @@ impl Parse for Element {
             } else {
                 break;
             }
+            bare = false;
         }
 
         let attributes = {
             if input.peek(syn::token::Bracket) {
+                bare = false;
                 let attributes;
                 syn::bracketed!(attributes in input);
                 Punctuated::<Attribute, syn::Token![,]>::parse_terminated(&attributes)?
@@ -180,12 +1240,22 @@ impl Parse for Element {
                     vec![syn::parse_quote_spanned!(string.span() => #string)],
                     true,
                 )
+            } else if bare {
+                // Most likely an expression like `name` or `name.len()` missing its `@`.
+                let literal = literal(&name).unwrap_or_default();
+                let message = if input.is_empty() {
+                    format!("expected `;`, `{{` or a string literal after the element `{literal}`, write `@{literal}` to render the value of `{literal}`")
+                } else {
+                    format!("expected `;`, `{{` or a string literal after the element `{literal}`, put `@` before expressions to render them")
+                };
+                return Err(syn::Error::new(name.span(), message));
             } else {
                 return Err(lookahead.error());
             }
         };
 
         Ok(Element {
+            lints,
             name,
             id,
             classes,
@@ -196,7 +1266,7 @@ impl Parse for Element {
     }
 }
 
-impl Parse for If {
+impl<T: Block> Parse for If<T> {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut clauses = vec![input.parse()?];
         let mut default = None;
@@ -210,7 +1280,7 @@ impl Parse for If {
                     default = {
                         let default;
                         syn::braced!(default in input);
-                        Some(default.parse::<Many<_>>()?.0)
+                        Some(T::block(&default)?)
                     };
                     break;
                 }
@@ -222,13 +1292,13 @@ impl Parse for If {
     }
 }
 
-impl Parse for IfClause {
+impl<T: Block> Parse for IfClause<T> {
     fn parse(input: ParseStream) -> Result<Self> {
         let test = input.parse()?;
         let consequent = {
             let consequent;
             syn::braced!(consequent in input);
-            consequent.parse::<Many<_>>()?.0
+            T::block(&consequent)?
         };
         Ok(IfClause { test, consequent })
     }
@@ -250,7 +1320,7 @@ impl Parse for IfClauseTest {
     }
 }
 
-impl Parse for Match {
+impl<T: Block> Parse for Match<T> {
     fn parse(input: ParseStream) -> Result<Self> {
         let expr = syn::Expr::parse_without_eager_brace(input)?;
         let inner;
@@ -260,7 +1330,7 @@ impl Parse for Match {
     }
 }
 
-impl Parse for MatchClause {
+impl<T: Block> Parse for MatchClause<T> {
     fn parse(input: ParseStream) -> Result<Self> {
         let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
         let guard = if input.peek(syn::Token![if]) {
@@ -270,9 +1340,38 @@ impl Parse for MatchClause {
             None
         };
         let _: syn::Token![=>] = input.parse()?;
-        let inner;
-        syn::braced!(inner in input);
-        let consequent = inner.parse::<Many<_>>()?.0;
+        // Like in Rust, an arm is either a block or a single item, which is followed by a comma
+        // unless it's the last arm or ends with braces.
+        let consequent = if input.peek(syn::token::Brace) {
+            let inner;
+            syn::braced!(inner in input);
+            T::block(&inner)?
+        } else {
+            let message =
+                "expected `,` after this `@match` arm, arms without braces are separated by commas";
+            let start = input.span();
+            // An item has no `=>` outside of brackets, so one before the next `,` belongs to the
+            // next arm. The item is parsed on a fork then, as it would otherwise take a pattern
+            // like `(_, 0)` as the arguments of a call, failing with an unrelated error.
+            let consequent = if peek_next_arm(input) {
+                let fork = input.fork();
+                match fork.parse() {
+                    Ok(consequent) if ends_with_brace(input.cursor(), &fork) => {
+                        input.advance_to(&fork);
+                        consequent
+                    }
+                    _ => return Err(syn::Error::new(start, message)),
+                }
+            } else {
+                let consequent = input.parse()?;
+                if !input.is_empty() {
+                    let _: syn::Token![,] =
+                        input.parse().map_err(|_| syn::Error::new(start, message))?;
+                }
+                consequent
+            };
+            vec![consequent]
+        };
         Ok(MatchClause {
             pat,
             guard,
@@ -281,6 +1380,41 @@ impl Parse for MatchClause {
     }
 }
 
+/// Whether the last token between `start` and `input` is a group in braces.
+fn ends_with_brace(start: syn::buffer::Cursor, input: ParseStream) -> bool {
+    let end = input.cursor();
+    let mut cursor = start;
+    let mut brace = false;
+    while cursor < end {
+        match cursor.token_tree() {
+            Some((token, next)) => {
+                brace = matches!(
+                    &token,
+                    proc_macro2::TokenTree::Group(group)
+                        if group.delimiter() == proc_macro2::Delimiter::Brace
+                );
+                cursor = next;
+            }
+            None => break,
+        }
+    }
+    brace
+}
+
+/// Whether a `=>` comes before the next `,` in `input`.
+fn peek_next_arm(input: ParseStream) -> bool {
+    let fork = input.fork();
+    while !fork.is_empty() && !fork.peek(syn::Token![,]) {
+        if fork.peek(syn::Token![=>]) {
+            return true;
+        }
+        if fork.parse::<proc_macro2::TokenTree>().is_err() {
+            break;
+        }
+    }
+    false
+}
+
 impl Parse for For {
     fn parse(input: ParseStream) -> Result<Self> {
         let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
@@ -293,14 +1427,64 @@ impl Parse for For {
     }
 }
 
+/// What the braces of an `@if` or `@match` contain.
+pub trait Block: Parse {
+    fn block(input: ParseStream) -> Result<Vec<Self>>;
+}
+
+impl Block for Node {
+    fn block(input: ParseStream) -> Result<Vec<Self>> {
+        Ok(input.parse::<Many<_>>()?.0)
+    }
+}
+
+impl Block for Attribute {
+    fn block(input: ParseStream) -> Result<Vec<Self>> {
+        Ok(
+            Punctuated::<Attribute, syn::Token![,]>::parse_terminated(input)?
+                .into_iter()
+                .collect(),
+        )
+    }
+}
+
 impl Parse for Attribute {
     fn parse(input: ParseStream) -> Result<Self> {
         if input.peek(syn::Token![..]) {
             let _: syn::Token![..] = input.parse()?;
             return Ok(Attribute::Many(input.parse()?));
         }
+        if input.peek(syn::Token![@]) && input.peek2(syn::token::If) {
+            let _: syn::Token![@] = input.parse()?;
+            let _: syn::token::If = input.parse()?;
+            return Ok(Attribute::If(input.parse()?));
+        }
+        if input.peek(syn::Token![@]) && input.peek2(syn::token::Match) {
+            let _: syn::Token![@] = input.parse()?;
+            let _: syn::token::Match = input.parse()?;
+            return Ok(Attribute::Match(input.parse()?));
+        }
 
-        let name = identifier_or_string_literal_or_expression(input)?;
+        // Framework specific names like Alpine's `@click` and Vue's `:class`.
+        let name = if input.peek(syn::Token![@]) || input.peek(syn::Token![:]) {
+            let sigil = if input.peek(syn::Token![@]) {
+                input.parse::<syn::Token![@]>()?;
+                "@"
+            } else {
+                input.parse::<syn::Token![:]>()?;
+                ":"
+            };
+            let name = identifier_or_string_literal_or_expression(input)?;
+            match literal(&name) {
+                Some(literal) => {
+                    let string = LitStr::new(&format!("{sigil}{literal}"), name.span());
+                    syn::parse_quote_spanned!(name.span() => #string)
+                }
+                None => return Err(syn::Error::new(name.span(), "expected an attribute name")),
+            }
+        } else {
+            identifier_or_string_literal_or_expression(input)?
+        };
         let value = if input.peek(syn::Token![=]) {
             let _: syn::Token![=] = input.parse()?;
             input.parse()?
@@ -317,12 +1501,153 @@ impl Parse for Attribute {
 pub struct Many<P>(pub Vec<P>);
 
 impl<P: Parse> Parse for Many<P> {
+    /// Items with errors are skipped so that the errors in the ones after them are reported as
+    /// well.
     fn parse(input: ParseStream) -> Result<Self> {
         let mut items = Vec::new();
+        let mut errors: Option<syn::Error> = None;
         while !input.is_empty() {
-            items.push(input.parse()?);
+            let fork = input.fork();
+            match fork.parse() {
+                Ok(item) => {
+                    input.advance_to(&fork);
+                    items.push(item);
+                }
+                Err(error) => {
+                    match &mut errors {
+                        Some(errors) => errors.combine(error),
+                        None => errors = Some(error),
+                    }
+                    skip(input, &fork)?;
+                }
+            }
+        }
+        match errors {
+            Some(errors) => Err(errors),
+            None => Ok(Many(items)),
         }
-        Ok(Many(items))
+    }
+}
+
+/// Skips the rest of an item which failed to parse at `fork`. If the error was inside braces,
+/// parsing continues after them, otherwise after the next `;` or braces.
+fn skip(input: ParseStream, fork: ParseStream) -> Result<()> {
+    use proc_macro2::{Delimiter, TokenTree};
+    let braces = |token: &TokenTree| matches!(token, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace);
+    input.step(|cursor| {
+        let mut rest = *cursor;
+        let mut after_braces = false;
+        while rest != fork.cursor() {
+            match rest.token_tree() {
+                Some((token, next)) => {
+                    after_braces = braces(&token);
+                    rest = next;
+                }
+                None => break,
+            }
+        }
+        if !after_braces {
+            while let Some((token, next)) = rest.token_tree() {
+                rest = next;
+                if braces(&token)
+                    || matches!(&token, TokenTree::Punct(punct) if punct.as_char() == ';')
+                {
+                    break;
+                }
+            }
+        }
+        Ok(((), rest))
+    })
+}
+
+fn fields(input: ParseStream) -> Result<(Vec<syn::Field>, Vec<Props>)> {
+    let mut props = Vec::new();
+    if input.peek(syn::token::Paren) {
+        let fields;
+        syn::parenthesized!(fields in input);
+        let fields = Punctuated::<_, syn::token::Comma>::parse_terminated_with(&fields, field)?
+            .into_iter()
+            .map(|(field, field_props)| {
+                props.extend(field_props);
+                field
+            })
+            .collect();
+        Ok((fields, props))
+    } else {
+        Ok((Vec::new(), props))
+    }
+}
+
+/// Parses a field of a template, which may be written as `..name: Type { fields }` to bind
+/// `fields` of it in the template, or as `..name: Type` to bind all of them.
+fn field(input: ParseStream) -> Result<(syn::Field, Option<Props>)> {
+    if !input.peek(syn::Token![..]) {
+        return Ok((syn::Field::parse_named(input)?, None));
+    }
+    let _: syn::Token![..] = input.parse()?;
+    let name: syn::Ident = input.parse()?;
+    let colon: syn::Token![:] = input.parse()?;
+    let ty: syn::Type = input.parse()?;
+    let path = struct_path(&ty).ok_or_else(|| {
+        syn::Error::new(
+            ty.span(),
+            format!(
+                "expected the type of `{name}` to be a struct, like `PageProps` or `&'a PageProps`"
+            ),
+        )
+    })?;
+    // Without a list, all the fields of `Type` are bound once `Define` knows them.
+    let (fields, listed) = if input.peek(syn::token::Brace) {
+        let inner;
+        syn::braced!(inner in input);
+        let fields = Punctuated::<syn::Ident, syn::token::Comma>::parse_terminated(&inner)?;
+        (fields.into_iter().collect(), true)
+    } else {
+        (Vec::new(), false)
+    };
+    let props = Props {
+        name: name.clone(),
+        path,
+        ty: struct_type(&ty).clone(),
+        fields,
+        listed,
+    };
+    let field = syn::Field {
+        attrs: Vec::new(),
+        vis: syn::Visibility::Inherited,
+        mutability: syn::FieldMutability::None,
+        ident: Some(name),
+        colon_token: Some(colon),
+        ty,
+    };
+    Ok((field, Some(props)))
+}
+
+/// Returns the type `ty` refers to, without the references.
+fn struct_type(ty: &syn::Type) -> &syn::Type {
+    match ty {
+        syn::Type::Reference(reference) => struct_type(&reference.elem),
+        syn::Type::Paren(paren) => struct_type(&paren.elem),
+        syn::Type::Group(group) => struct_type(&group.elem),
+        _ => ty,
+    }
+}
+
+/// Returns the path of the struct `ty` is or refers to, without generic arguments, which can be
+/// used in a pattern. These are inferred from the value being destructured.
+fn struct_path(ty: &syn::Type) -> Option<syn::Path> {
+    match ty {
+        syn::Type::Reference(reference) => struct_path(&reference.elem),
+        syn::Type::Paren(paren) => struct_path(&paren.elem),
+        syn::Type::Group(group) => struct_path(&group.elem),
+        syn::Type::Path(path) if path.qself.is_none() => {
+            let mut path = path.path.clone();
+            for segment in &mut path.segments {
+                segment.arguments = syn::PathArguments::None;
+            }
+            Some(path)
+        }
+        _ => None,
     }
 }
 
@@ -331,7 +1656,31 @@ fn identifier_or_string_literal_or_expression(input: ParseStream) -> Result<syn:
     let lookahead = input.lookahead1();
     if lookahead.peek(syn::Ident::peek_any) {
         let ident = syn::Ident::parse_any(input)?;
-        let string = LitStr::new(&ident.unraw().to_string(), ident.span());
+        let mut name = ident.unraw().to_string();
+        // Names joined with dashes like `data-id` and namespace prefixed names like `xlink:href`.
+        loop {
+            if input.peek(syn::Token![-])
+                && (input.peek2(syn::Ident::peek_any) || input.peek2(syn::LitInt))
+            {
+                let _: syn::Token![-] = input.parse()?;
+                name.push('-');
+            } else if input.peek(syn::Token![:])
+                && !input.peek(syn::Token![::])
+                && input.peek2(syn::Ident::peek_any)
+            {
+                let _: syn::Token![:] = input.parse()?;
+                name.push(':');
+            } else {
+                break;
+            }
+            if input.peek(syn::LitInt) {
+                // Like the `2fa` in `data-2fa`.
+                name.push_str(&input.parse::<syn::LitInt>()?.to_string());
+            } else {
+                name.push_str(&syn::Ident::parse_any(input)?.unraw().to_string());
+            }
+        }
+        let string = LitStr::new(&name, ident.span());
         Ok(syn::parse_quote_spanned!(ident.span() => #string))
     } else if lookahead.peek(syn::LitStr) {
         let lit_str = &input.parse::<syn::LitStr>()?;
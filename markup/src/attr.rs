@@ -0,0 +1,243 @@
+//! Values of enumerated attributes, for when they aren't known at compile time.
+//!
+//! ```rust
+//! use markup::attr::{InputType, Loading};
+//!
+//! markup::define! {
+//!     Field(kind: InputType) {
+//!         input[type = kind];
+//!         img[src = "/spinner.gif", loading = Loading::Lazy];
+//!     }
+//! }
+//!
+//! assert_eq!(
+//!     Field { kind: InputType::Email }.to_string(),
+//!     r#"<input type="email"><img src="/spinner.gif" loading="lazy">"#
+//! );
+//! ```
+
+use crate::{Render, RenderAttributeValue};
+
+macro_rules! enumerated {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $value,)+
+                }
+            }
+        }
+
+        impl Render for $name {
+            #[inline]
+            fn render(&self, writer: &mut impl std::fmt::Write) -> std::fmt::Result {
+                writer.write_str(self.as_str())
+            }
+        }
+
+        impl RenderAttributeValue for $name {}
+
+        impl std::fmt::Display for $name {
+            #[inline]
+            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                fmt.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+enumerated! {
+    /// The `type` of an `input`.
+    InputType {
+        Button => "button",
+        Checkbox => "checkbox",
+        Color => "color",
+        Date => "date",
+        DatetimeLocal => "datetime-local",
+        Email => "email",
+        File => "file",
+        Hidden => "hidden",
+        Image => "image",
+        Month => "month",
+        Number => "number",
+        Password => "password",
+        Radio => "radio",
+        Range => "range",
+        Reset => "reset",
+        Search => "search",
+        Submit => "submit",
+        Tel => "tel",
+        Text => "text",
+        Time => "time",
+        Url => "url",
+        Week => "week",
+    }
+}
+
+enumerated! {
+    /// The `type` of a `button`.
+    ButtonType {
+        Button => "button",
+        Reset => "reset",
+        Submit => "submit",
+    }
+}
+
+enumerated! {
+    /// The `method` of a `form` or the `formmethod` of a `button` or `input`.
+    Method {
+        Dialog => "dialog",
+        Get => "get",
+        Post => "post",
+    }
+}
+
+enumerated! {
+    /// The `enctype` of a `form` or the `formenctype` of a `button` or `input`.
+    Enctype {
+        UrlEncoded => "application/x-www-form-urlencoded",
+        Multipart => "multipart/form-data",
+        Plain => "text/plain",
+    }
+}
+
+enumerated! {
+    /// The `target` of a link or form. Other targets are names of browsing contexts, which can be
+    /// rendered as strings.
+    Target {
+        Blank => "_blank",
+        Parent => "_parent",
+        /// `_self`.
+        Current => "_self",
+        Top => "_top",
+    }
+}
+
+enumerated! {
+    /// A link type, as used in `rel`. Several of them can be rendered with a tuple like
+    /// `(Rel::Noopener, " ", Rel::Noreferrer)`.
+    Rel {
+        Alternate => "alternate",
+        Author => "author",
+        Bookmark => "bookmark",
+        Canonical => "canonical",
+        DnsPrefetch => "dns-prefetch",
+        Expect => "expect",
+        External => "external",
+        Help => "help",
+        Icon => "icon",
+        License => "license",
+        Manifest => "manifest",
+        Me => "me",
+        Modulepreload => "modulepreload",
+        Next => "next",
+        Nofollow => "nofollow",
+        Noopener => "noopener",
+        Noreferrer => "noreferrer",
+        Opener => "opener",
+        Pingback => "pingback",
+        Preconnect => "preconnect",
+        Prefetch => "prefetch",
+        Preload => "preload",
+        Prev => "prev",
+        PrivacyPolicy => "privacy-policy",
+        Search => "search",
+        Stylesheet => "stylesheet",
+        Tag => "tag",
+        TermsOfService => "terms-of-service",
+    }
+}
+
+enumerated! {
+    /// The `autocomplete` of a form control. Only the most common autofill field names are
+    /// included, others can be rendered as strings.
+    Autocomplete {
+        On => "on",
+        Off => "off",
+        Name => "name",
+        GivenName => "given-name",
+        FamilyName => "family-name",
+        Nickname => "nickname",
+        Username => "username",
+        NewPassword => "new-password",
+        CurrentPassword => "current-password",
+        OneTimeCode => "one-time-code",
+        Email => "email",
+        Tel => "tel",
+        Url => "url",
+        Organization => "organization",
+        StreetAddress => "street-address",
+        PostalCode => "postal-code",
+        Country => "country",
+        CountryName => "country-name",
+        Bday => "bday",
+        CcName => "cc-name",
+        CcNumber => "cc-number",
+        CcExp => "cc-exp",
+        CcCsc => "cc-csc",
+        Language => "language",
+    }
+}
+
+enumerated! {
+    /// The `loading` of an `img` or `iframe`.
+    Loading {
+        Eager => "eager",
+        Lazy => "lazy",
+    }
+}
+
+enumerated! {
+    /// The `dir` of any element.
+    Dir {
+        Auto => "auto",
+        Ltr => "ltr",
+        Rtl => "rtl",
+    }
+}
+
+enumerated! {
+    /// The `decoding` of an `img`.
+    Decoding {
+        Async => "async",
+        Auto => "auto",
+        Sync => "sync",
+    }
+}
+
+enumerated! {
+    /// The `crossorigin` of a media element, `link` or `script`.
+    CrossOrigin {
+        Anonymous => "anonymous",
+        UseCredentials => "use-credentials",
+    }
+}
+
+enumerated! {
+    /// The `referrerpolicy` of a link, `iframe`, `img` or `script`.
+    ReferrerPolicy {
+        NoReferrer => "no-referrer",
+        NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+        Origin => "origin",
+        OriginWhenCrossOrigin => "origin-when-cross-origin",
+        SameOrigin => "same-origin",
+        StrictOrigin => "strict-origin",
+        StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+        UnsafeUrl => "unsafe-url",
+    }
+}
+
+enumerated! {
+    /// The `fetchpriority` of an `img`, `link` or `script`.
+    FetchPriority {
+        Auto => "auto",
+        High => "high",
+        Low => "low",
+    }
+}
@@ -1,13 +1,21 @@
 use std::fmt::Write;
 
-pub use markup_proc_macro::{define, new};
+pub use markup_proc_macro::{component, define, include, new, Props, Render};
 
+pub mod attr;
 mod escape;
+mod html;
+mod pretty;
+
+pub use pretty::{pretty, Pretty};
 
 pub trait Render {
     fn render(&self, writer: &mut impl std::fmt::Write) -> std::fmt::Result;
 }
 
+/// Implemented by `#[derive(markup::Props)]` for structs which templates can take as `..name: Type`.
+pub trait Props {}
+
 pub trait RenderAttributeValue: Render {
     #[inline]
     fn is_none(&self) -> bool {
@@ -105,7 +113,20 @@ impl<T: Render> Render for Option<T> {
 impl<T: RenderAttributeValue> RenderAttributeValue for Option<T> {
     #[inline]
     fn is_none(&self) -> bool {
-        self.is_none()
+        match self {
+            Some(t) => t.is_none(),
+            None => true,
+        }
+    }
+
+    #[inline]
+    fn is_true(&self) -> bool {
+        matches!(self, Some(t) if t.is_true())
+    }
+
+    #[inline]
+    fn is_false(&self) -> bool {
+        matches!(self, Some(t) if t.is_false())
     }
 }
 
@@ -243,7 +264,87 @@ impl<'a> Render for DynRender<'a> {
 impl<'a> std::fmt::Display for DynRender<'a> {
     #[inline]
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        Render::render(self, fmt)
+        if fmt.alternate() {
+            Render::render(&pretty(self), fmt)
+        } else {
+            Render::render(self, fmt)
+        }
+    }
+}
+
+pub struct FromFn<F>(F);
+
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+    F: Fn(&mut dyn std::fmt::Write) -> std::fmt::Result,
+{
+    FromFn(f)
+}
+
+impl<F> Render for FromFn<F>
+where
+    F: Fn(&mut dyn std::fmt::Write) -> std::fmt::Result,
+{
+    #[inline]
+    fn render(&self, writer: &mut impl std::fmt::Write) -> std::fmt::Result {
+        (self.0)(writer)
+    }
+}
+
+impl<F> std::fmt::Display for FromFn<F>
+where
+    F: Fn(&mut dyn std::fmt::Write) -> std::fmt::Result,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if fmt.alternate() {
+            Render::render(&pretty(self), fmt)
+        } else {
+            Render::render(self, fmt)
+        }
+    }
+}
+
+/// A template from `markup::new!` with parameters, applied to `arguments`.
+#[doc(hidden)]
+pub struct Call<T, A> {
+    template: std::rc::Rc<T>,
+    arguments: A,
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn call<T, A>(template: std::rc::Rc<T>, arguments: A) -> Call<T, A>
+where
+    T: Fn(&mut dyn std::fmt::Write, &A) -> std::fmt::Result,
+{
+    Call {
+        template,
+        arguments,
+    }
+}
+
+impl<T, A> Render for Call<T, A>
+where
+    T: Fn(&mut dyn std::fmt::Write, &A) -> std::fmt::Result,
+{
+    #[inline]
+    fn render(&self, writer: &mut impl std::fmt::Write) -> std::fmt::Result {
+        (self.template)(writer, &self.arguments)
+    }
+}
+
+impl<T, A> std::fmt::Display for Call<T, A>
+where
+    T: Fn(&mut dyn std::fmt::Write, &A) -> std::fmt::Result,
+{
+    #[inline]
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if fmt.alternate() {
+            Render::render(&pretty(self), fmt)
+        } else {
+            Render::render(self, fmt)
+        }
     }
 }
 
@@ -251,3 +352,78 @@ impl<'a> std::fmt::Display for DynRender<'a> {
 pub fn doctype() -> impl Render {
     raw("<!DOCTYPE html>")
 }
+
+#[inline]
+pub fn xml_declaration() -> impl Render {
+    raw(r#"<?xml version="1.0" encoding="UTF-8"?>"#)
+}
+
+/// Panics if the element `name` is void but has an end tag, or if it's a non-void HTML element
+/// without one. Used by templates in debug builds to check elements whose name isn't known at
+/// compile time.
+#[doc(hidden)]
+#[track_caller]
+pub fn check_element(name: &(impl Render + ?Sized), end_tag: bool) {
+    let mut name_ = String::new();
+    let _ = name.render(&mut name_);
+    let lowercase = name_.to_ascii_lowercase();
+    let void = html::VOID.contains(&lowercase.as_str());
+    if void && end_tag {
+        panic!(
+            "`{}` is a void element and can't have children or an end tag",
+            name_
+        );
+    }
+    if !void && !end_tag && html::ELEMENTS.contains(&lowercase.as_str()) {
+        panic!("`{}` isn't a void element and needs an end tag", name_);
+    }
+}
+
+/// Whether the attribute `name` takes the values `true` and `false` instead of being present or
+/// absent, like `aria-*` and `draggable`. Used by templates for attributes whose name isn't known
+/// at compile time.
+#[doc(hidden)]
+pub fn is_true_false(name: &(impl Render + ?Sized)) -> bool {
+    let mut start = NameStart {
+        bytes: [0; 16],
+        len: 0,
+    };
+    let _ = name.render(&mut start);
+    html::is_true_false(&start.bytes[..start.len])
+}
+
+/// Keeps the first bytes of an attribute name written through it, which are enough to tell
+/// whether it takes `true` and `false`, so `is_true_false` doesn't need to allocate. It has room
+/// for one more byte than the longest such name, so longer names don't match it.
+struct NameStart {
+    bytes: [u8; 16],
+    len: usize,
+}
+
+impl std::fmt::Write for NameStart {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let count = s.len().min(self.bytes.len() - self.len);
+        self.bytes[self.len..self.len + count].copy_from_slice(&s.as_bytes()[..count]);
+        self.len += count;
+        // Stop rendering once the rest of the name can't make a difference.
+        if self.len == self.bytes.len() {
+            Err(std::fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Drops characters which aren't allowed in XML from everything written through it. Used by
+/// templates in XML mode.
+#[doc(hidden)]
+pub struct XmlWriter<'a, W>(pub &'a mut W);
+
+impl<W: std::fmt::Write> std::fmt::Write for XmlWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for part in s.split(|c| !html::is_xml_char(c)) {
+            self.0.write_str(part)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use crate::html::{BLOCK, RAW, VOID};
+use crate::Render;
+
+pub struct Pretty<'a, T: ?Sized>(&'a T);
+
+pub fn pretty<T: Render + ?Sized>(template: &T) -> Pretty<'_, T> {
+    Pretty(template)
+}
+
+impl<T: Render + ?Sized> Render for Pretty<'_, T> {
+    fn render(&self, writer: &mut impl std::fmt::Write) -> std::fmt::Result {
+        let mut indent = Indent::new(writer);
+        self.0.render(&mut indent)?;
+        indent.finish()
+    }
+}
+
+impl<T: Render + ?Sized> std::fmt::Display for Pretty<'_, T> {
+    #[inline]
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Render::render(self, fmt)
+    }
+}
+
+enum State {
+    Text,
+    Tag,
+    Attributes(Option<char>),
+    Comment(usize),
+    Raw,
+}
+
+// Indents the HTML written to it on the fly.
+struct Indent<'a, W> {
+    writer: &'a mut W,
+    state: State,
+    // The tag whose name is being read, including any leading `/`, `!` or `?`.
+    tag: String,
+    depth: usize,
+    start: bool,
+    // A block element was just opened or closed, so any following content goes on a new line.
+    pending: bool,
+    // The last thing written is the opening tag of a block element.
+    opened: bool,
+    // The raw text element we're inside and how much of its closing tag has been seen so far.
+    raw: &'static str,
+    closing: String,
+    raw_closing: bool,
+    last: char,
+}
+
+impl<'a, W: std::fmt::Write> Indent<'a, W> {
+    fn new(writer: &'a mut W) -> Self {
+        Indent {
+            writer,
+            state: State::Text,
+            tag: String::new(),
+            depth: 0,
+            start: true,
+            pending: false,
+            opened: false,
+            raw: "",
+            closing: String::new(),
+            raw_closing: false,
+            last: '\0',
+        }
+    }
+
+    fn finish(mut self) -> std::fmt::Result {
+        match self.state {
+            State::Tag => {
+                self.state = State::Text;
+                self.text('<')?;
+                let tag = std::mem::take(&mut self.tag);
+                tag.chars().try_for_each(|c| self.text(c))
+            }
+            State::Raw => self.writer.write_str(&self.closing),
+            _ => Ok(()),
+        }
+    }
+
+    fn put(&mut self, c: char) -> std::fmt::Result {
+        self.start = false;
+        self.last = c;
+        self.writer.write_char(c)
+    }
+
+    fn newline(&mut self) -> std::fmt::Result {
+        if !self.start {
+            self.writer.write_char('\n')?;
+            for _ in 0..self.depth {
+                self.writer.write_str("  ")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, c: char) -> std::fmt::Result {
+        if self.pending {
+            if c.is_whitespace() {
+                return Ok(());
+            }
+            self.newline()?;
+            self.pending = false;
+        }
+        self.opened = false;
+        self.put(c)
+    }
+
+    fn name(&self) -> String {
+        self.tag.trim_start_matches('/').to_ascii_lowercase()
+    }
+
+    fn is_block(name: &str) -> bool {
+        name.starts_with('!') || name.starts_with('?') || BLOCK.contains(&name)
+    }
+
+    fn open_tag(&mut self) -> std::fmt::Result {
+        let name = self.name();
+        let block = Self::is_block(&name);
+        if self.tag.starts_with('/') && block {
+            self.depth = self.depth.saturating_sub(1);
+            if !self.opened && !self.raw_closing {
+                self.newline()?;
+            }
+        } else if block || self.pending {
+            self.newline()?;
+        }
+        self.pending = false;
+        self.opened = false;
+        self.put('<')?;
+        let tag = std::mem::take(&mut self.tag);
+        for c in tag.chars() {
+            self.put(c)?;
+        }
+        self.tag = tag;
+        Ok(())
+    }
+
+    fn close_tag(&mut self) -> std::fmt::Result {
+        let self_closing = self.last == '/';
+        self.put('>')?;
+        let name = self.name();
+        let block = Self::is_block(&name);
+        self.state = State::Text;
+        self.raw_closing = false;
+        if self.tag.starts_with('/')
+            || self_closing
+            || VOID.contains(&name.as_str())
+            || name.starts_with('!')
+            || name.starts_with('?')
+        {
+            self.pending = block;
+        } else {
+            if let Some(raw) = RAW.iter().find(|raw| **raw == name) {
+                self.raw = raw;
+                self.state = State::Raw;
+            }
+            if block {
+                self.depth += 1;
+                self.opened = self.raw.is_empty();
+                self.pending = self.raw.is_empty();
+            }
+        }
+        Ok(())
+    }
+
+    fn char(&mut self, c: char) -> std::fmt::Result {
+        match self.state {
+            State::Text => {
+                if c == '<' {
+                    self.tag.clear();
+                    self.state = State::Tag;
+                    Ok(())
+                } else {
+                    self.text(c)
+                }
+            }
+            State::Tag => {
+                if c.is_alphanumeric()
+                    || matches!(c, '-' | ':' | '_' | '.')
+                    || (self.tag.is_empty() && matches!(c, '/' | '!' | '?'))
+                {
+                    self.tag.push(c);
+                    if self.tag == "!--" {
+                        self.tag.clear();
+                        self.state = State::Text;
+                        "<!--".chars().try_for_each(|c| self.text(c))?;
+                        self.state = State::Comment(2);
+                    }
+                    Ok(())
+                } else if self.tag.is_empty() || self.tag == "/" {
+                    // Not a tag after all.
+                    self.state = State::Text;
+                    self.text('<')?;
+                    let tag = std::mem::take(&mut self.tag);
+                    tag.chars().try_for_each(|c| self.text(c))?;
+                    self.char(c)
+                } else {
+                    self.open_tag()?;
+                    self.state = State::Attributes(None);
+                    self.char(c)
+                }
+            }
+            State::Attributes(Some(quote)) => {
+                if c == quote {
+                    self.state = State::Attributes(None);
+                }
+                self.put(c)
+            }
+            State::Attributes(None) => match c {
+                '"' | '\'' => {
+                    self.state = State::Attributes(Some(c));
+                    self.put(c)
+                }
+                '>' => self.close_tag(),
+                _ => self.put(c),
+            },
+            State::Comment(dashes) => {
+                self.state = match c {
+                    '-' => State::Comment(dashes + 1),
+                    '>' if dashes >= 2 => State::Text,
+                    _ => State::Comment(0),
+                };
+                self.put(c)
+            }
+            State::Raw => {
+                if self.closing.is_empty() && c != '<' {
+                    return self.put(c);
+                }
+                self.closing.push(c);
+                let closing = self.closing.to_ascii_lowercase();
+                let target = format!("</{}", self.raw);
+                if closing == target {
+                    self.tag = self.closing[1..].to_string();
+                    self.closing.clear();
+                    self.raw = "";
+                    self.raw_closing = true;
+                    self.state = State::Tag;
+                } else if !target.starts_with(&closing) {
+                    let closing = std::mem::take(&mut self.closing);
+                    let (rest, last) = closing.split_at(closing.len() - c.len_utf8());
+                    self.writer.write_str(rest)?;
+                    if c == '<' {
+                        self.closing.push(c);
+                    } else {
+                        self.writer.write_str(last)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<W: std::fmt::Write> std::fmt::Write for Indent<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        s.chars().try_for_each(|c| self.char(c))
+    }
+}
+
+#[test]
+fn test() {
+    use std::fmt::Write;
+
+    t("", "");
+    t("<div></div>", "<div></div>");
+    t("<div>a<b>b</b> c</div>", "<div>\n  a<b>b</b> c\n</div>");
+    t(
+        "<!DOCTYPE html><html><head><title>a</title></head><body><p>b<br>c</p><hr><p>d</p></body></html>",
+        "<!DOCTYPE html>\n<html>\n  <head>\n    <title>\n      a\n    </title>\n  </head>\n  <body>\n    <p>\n      b<br>c\n    </p>\n    <hr>\n    <p>\n      d\n    </p>\n  </body>\n</html>",
+    );
+    t(
+        "<ul>\n<li a=\"<div>\">x</li>\n<li/><!-- <p> --></ul>",
+        "<ul>\n  <li a=\"<div>\">\n    x\n  </li>\n  <li/>\n  <!-- <p> -->\n</ul>",
+    );
+    t(
+        "<div><pre>  <p>a</p>\n b</pre><textarea> <div> </textarea><script>if (a</b) {}</SCRIPT></div>",
+        "<div>\n  <pre>  <p>a</p>\n b</pre>\n  <textarea> <div> </textarea>\n  <script>if (a</b) {}</SCRIPT>\n</div>",
+    );
+    t("a < b <", "a < b <");
+
+    fn t(input: &str, output: &str) {
+        let mut string = String::new();
+        let mut indent = Indent::new(&mut string);
+        indent.write_str(input).unwrap();
+        indent.finish().unwrap();
+        assert_eq!(string, output);
+    }
+}
@@ -387,3 +387,1139 @@ t! {
         data: [("foo", "bar"), ("baz", "quux")].iter().cloned().collect()
     } => r#"<div data-baz="quux" data-foo="bar"></div>"#,
 }
+
+t! {
+    t17,
+    {
+        #[derive(Clone)]
+        enum A<'a> {
+            Loading { "Loading..." }
+            Empty() { p.empty { "No items." } }
+            /// The loaded items.
+            Loaded(items: &'a [&'a str]) {
+                ul {
+                    @for item in *items {
+                        li { @item }
+                    }
+                }
+            }
+            Error(message: String, code: u16) {
+                p.error { @message " (" @code ")" }
+            }
+        }
+    },
+    A::Loading => "Loading...",
+    A::Empty => r#"<p class="empty">No items.</p>"#,
+    A::Loaded { items: &["foo", "<bar>"] } => "<ul><li>foo</li><li>&lt;bar&gt;</li></ul>",
+    A::Error { message: "Not found".into(), code: 404 }.clone() => r#"<p class="error">Not found (404)</p>"#,
+}
+
+#[test]
+fn t18() {
+    #[derive(Clone, Debug, markup::Render)]
+    #[markup {
+        article.post[data_id = id] {
+            h2 { @title }
+            @for tag in tags {
+                span.tag { @tag }
+            }
+        }
+    }]
+    struct Post<'a, T: markup::Render> {
+        id: u32,
+        title: &'a str,
+        tags: Vec<T>,
+    }
+
+    #[derive(markup::Render)]
+    #[markup { hr; }]
+    struct Separator;
+
+    let post = Post {
+        id: 1,
+        title: "Hello <World>",
+        tags: vec!["a", "b"],
+    };
+    assert_eq!(
+        post.to_string(),
+        r#"<article class="post" data_id="1"><h2>Hello &lt;World&gt;</h2><span class="tag">a</span><span class="tag">b</span></article>"#
+    );
+    assert_eq!(Separator.to_string(), "<hr>");
+}
+
+t! {
+    t19,
+    {
+        A<'a>(title: &'a str, items: &'a [u32]) include!("tests/templates/page.markup")
+        B {
+            @A { title: "B", items: &[] }
+        }
+    },
+    A { title: "<A>", items: &[1, 2] } => r#"<html><head><title>&lt;A&gt;</title></head><body><p class="item">1</p><p class="item">2</p></body></html>"#,
+    B {} => "<html><head><title>B</title></head><body></body></html>",
+}
+
+#[test]
+fn t20() {
+    let name = "World";
+    let greeting = markup::include!("tests/templates/greeting.markup");
+    assert_eq!(greeting.to_string(), "Hello, World!");
+}
+
+#[test]
+fn t21() {
+    use markup::Render;
+
+    #[markup::component]
+    fn Card<'a>(title: &'a str, children: impl Render + 'a) -> impl Render + 'a {
+        markup::new! {
+            div.card {
+                h2 { @title }
+                @children
+            }
+        }
+    }
+
+    #[markup::component]
+    fn List<T: Render>(items: Vec<T>) -> impl Render + std::fmt::Display {
+        let count = items.len();
+        markup::new! {
+            ul[data_count = count] {
+                @for item in &items {
+                    li { @item }
+                }
+            }
+        }
+    }
+
+    assert_eq!(
+        List(vec!["<a>", "b"]).to_string(),
+        r#"<ul data_count="2"><li>&lt;a&gt;</li><li>b</li></ul>"#
+    );
+    assert_eq!(
+        markup::new!(@Card("<Title>", List(vec![1]))).to_string(),
+        r#"<div class="card"><h2>&lt;Title&gt;</h2><ul data_count="1"><li>1</li></ul></div>"#
+    );
+    assert_eq!(
+        markup::new!(@Card("A", "B") @Card("C", markup::new!(br;))).to_string(),
+        r#"<div class="card"><h2>A</h2>B</div><div class="card"><h2>C</h2><br></div>"#
+    );
+}
+
+#[test]
+fn t22() {
+    struct Item {
+        name: &'static str,
+        price: u32,
+    }
+
+    let currency = "$";
+    let items = &[
+        Item {
+            name: "<a>",
+            price: 1,
+        },
+        Item {
+            name: "b",
+            price: 2,
+        },
+    ];
+    let row = markup::new!(|item: &Item, index: usize| {
+        li[data_index = index] { @item.name ": " @currency @item.price }
+    });
+    assert_eq!(
+        row(&items[1], 5).to_string(),
+        r#"<li data_index="5">b: $2</li>"#
+    );
+    let separator = markup::new!(|| {
+        hr;
+    });
+    let list = markup::new! {
+        ul {
+            @for (index, item) in items.iter().enumerate() {
+                @row(item, index)
+            }
+        }
+        @separator()
+    };
+    assert_eq!(
+        list.to_string(),
+        r#"<ul><li data_index="0">&lt;a&gt;: $1</li><li data_index="1">b: $2</li></ul><hr>"#
+    );
+}
+
+t! {
+    t23,
+    {
+        A<'a>(names: &'a [&'a str]) {
+            @let row = markup::new!(|name: &str| { li { @name } });
+            ul {
+                @for name in names.iter() {
+                    @row(name)
+                }
+            }
+        }
+    },
+    A { names: &["a", "<b>"] } => "<ul><li>a</li><li>&lt;b&gt;</li></ul>",
+}
+
+#[test]
+fn t24() {
+    markup::define! {
+        Page<'a>(items: &'a [&'a str]) {
+            @markup::doctype()
+            html {
+                body {
+                    h1 { "Items" }
+                    ul {
+                        @for item in *items {
+                            li { a[href = "#"] { @item } " " em { "!" } }
+                        }
+                    }
+                    pre { "  a\n    b" }
+                }
+            }
+        }
+    }
+
+    let page = Page { items: &["x", "y"] };
+    let expected = r##"<!DOCTYPE html>
+<html>
+  <body>
+    <h1>
+      Items
+    </h1>
+    <ul>
+      <li>
+        <a href="#">x</a> <em>!</em>
+      </li>
+      <li>
+        <a href="#">y</a> <em>!</em>
+      </li>
+    </ul>
+    <pre>  a
+    b</pre>
+  </body>
+</html>"##;
+    assert_eq!(format!("{:#}", page), expected);
+    assert_eq!(markup::pretty(&page).to_string(), expected);
+    let page = &page;
+    assert_eq!(format!("{:#}", markup::new!(@page)), expected);
+    assert_eq!(format!("{}", page), page.to_string());
+}
+
+#[test]
+fn t25() {
+    markup::define! {
+        #[markup(minify)]
+        Page<'a>(items: &'a [&'a str]) {
+            html {
+                head {
+                    style { @markup::raw("
+                        body  >  p {
+                            color : red ;  /* comment */
+                        }
+                    ") }
+                    script { @markup::raw("
+                        // comment
+                        let a = 1 < 2;
+                        console.log( a )
+                    ") }
+                }
+                body {
+                    "  "
+                    p { "  Hello,   " b { "world" } " !  " }
+                    ul {
+                        @for item in *items {
+                            li { " " @item " " }
+                        }
+                    }
+                    pre { "  a\n    b  " }
+                }
+            }
+        }
+        #[markup(minify, omit_optional_tags)]
+        List<'a>(items: &'a [&'a str]) {
+            ul {
+                li { "a" }
+                li { "b" }
+                @for item in *items {
+                    li { @item }
+                }
+            }
+            table {
+                tr { td { "1" } td { "2" } }
+                tr { td { "3" } }
+            }
+            div { p { "x" } }
+            a { p { "y" } }
+        }
+    }
+
+    assert_eq!(
+        Page { items: &["x", "y"] }.to_string(),
+        "<html><head><style>body>p{color :red}</style><script>let a=1<2;console.log(a)</script></head><body><p>Hello, <b>world</b> !</p><ul><li>x</li><li>y</li></ul><pre>  a\n    b  </pre></body></html>"
+    );
+    assert_eq!(
+        List { items: &["c"] }.to_string(),
+        "<ul><li>a<li>b</li><li>c</li></ul><table><tr><td>1<td>2<tr><td>3</table><div><p>x</div><a><p>y</p></a>"
+    );
+}
+
+#[test]
+fn t26() {
+    markup::define! {
+        #[markup(xml)]
+        Feed<'a>(title: &'a str, entries: &'a [&'a str]) {
+            @markup::xml_declaration()
+            feed[xmlns = "http://www.w3.org/2005/Atom"] {
+                title { "Feed\u{1} " @title }
+                link[href = "https://example.com/"];
+                @for entry in *entries {
+                    entry { title { @entry } }
+                }
+            }
+        }
+        #[markup(xml)]
+        Sprite(hidden: bool) {
+            svg[xmlns = "http://www.w3.org/2000/svg", "xmlns:xlink" = "http://www.w3.org/1999/xlink"] {
+                symbol #icon[hidden = *hidden] {
+                    svg:rect[width = 10, height = 10];
+                }
+                r#use[xlink:href = "#icon"];
+            }
+        }
+    }
+
+    assert_eq!(
+        Feed {
+            title: "A & B\u{b}",
+            entries: &["<x>"]
+        }
+        .to_string(),
+        r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns="http://www.w3.org/2005/Atom"><title>Feed A &amp; B</title><link href="https://example.com/"/><entry><title>&lt;x&gt;</title></entry></feed>"#
+    );
+    assert_eq!(
+        Sprite { hidden: true }.to_string(),
+        r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink"><symbol id="icon" hidden="hidden"><svg:rect width="10" height="10"/></symbol><use xlink:href="#icon"/></svg>"##
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn t27() {
+    let render = |name: &'static str, end_tag: bool| {
+        std::panic::catch_unwind(move || {
+            if end_tag {
+                markup::new!(${name} {}).to_string()
+            } else {
+                markup::new!(${name};).to_string()
+            }
+        })
+        .map_err(|error| *error.downcast::<String>().unwrap())
+    };
+
+    assert_eq!(render("div", true), Ok("<div></div>".into()));
+    assert_eq!(render("BR", false), Ok("<BR>".into()));
+    assert_eq!(render("my-element", false), Ok("<my-element>".into()));
+    assert_eq!(
+        render("div", false),
+        Err("`div` isn't a void element and needs an end tag".into())
+    );
+    assert_eq!(
+        render("br", true),
+        Err("`br` is a void element and can't have children or an end tag".into())
+    );
+}
+
+#[test]
+fn t28() {
+    markup::define! {
+        #[markup(deny(content_model))]
+        Page<'a>(items: &'a [&'a str]) {
+            p { a[href = "/"] { b { "Home" } } }
+            ul {
+                @for item in *items {
+                    li { a[href = *item] { div { @item } } }
+                }
+            }
+            form { input[type = "hidden", name = "a"]; button { "Submit" } }
+            table { tr { td { "1" } } }
+            svg { a { p {} } }
+            #[markup(allow(content_model))]
+            p { div { "Escaped" } }
+        }
+    }
+
+    assert_eq!(
+        Page { items: &["x"] }.to_string(),
+        r#"<p><a href="/"><b>Home</b></a></p><ul><li><a href="x"><div>x</div></a></li></ul><form><input type="hidden" name="a"><button>Submit</button></form><table><tr><td>1</td></tr></table><svg><a><p></p></a></svg><p><div>Escaped</div></p>"#
+    );
+}
+
+#[test]
+fn t29() {
+    markup::define! {
+        #[markup(deny(attribute_names, element_names), attribute_prefixes("x-"))]
+        Form<'a>(attributes: &'a [(&'a str, &'a str)]) {
+            form[action = "/", method = "post", onsubmit = "return true", "accept-charset" = "utf-8"] {
+                input #name.wide[type = "text", "data-id" = 1, "aria-label" = "Name", "x-model" = "name", "hx-post" = "/"];
+                $"my-element"[anything = true] {}
+                svg[viewBox = "0 0 1 1", "xml:lang" = "en"] { path[d = "M0"]; }
+                #[markup(allow(attribute_names))]
+                div[foo = 1] {}
+                span[..*attributes] {}
+            }
+        }
+    }
+
+    assert_eq!(
+        Form {
+            attributes: &[("bar", "1")]
+        }
+        .to_string(),
+        r#"<form action="/" method="post" onsubmit="return true" accept-charset="utf-8"><input id="name" class="wide" type="text" data-id="1" aria-label="Name" x-model="name" hx-post="/"><my-element anything></my-element><svg viewBox="0 0 1 1" xml:lang="en"><path d="M0"></svg><div foo="1"></div><span bar="1"></span></form>"#
+    );
+}
+
+#[test]
+fn t30() {
+    use markup::attr::{ButtonType, Dir, Method, Rel, Target};
+
+    markup::define! {
+        #[markup(deny(attribute_values))]
+        Form(method: Method, button: ButtonType) {
+            form[method = method, target = "results", autocomplete = "off"] {
+                input[type = "EMAIL", autocomplete = "section-a shipping email"];
+                button[type = button, dir = Dir::Rtl] { "Send" }
+                a[href = "/", target = Target::Blank, rel = (Rel::Noopener, " ", Rel::Noreferrer)] {}
+                link[rel = "stylesheet preload", crossorigin = ""];
+                #[markup(allow(attribute_values))]
+                input[type = "emial"];
+            }
+        }
+    }
+
+    assert_eq!(
+        Form {
+            method: Method::Post,
+            button: ButtonType::Submit,
+        }
+        .to_string(),
+        r#"<form method="post" target="results" autocomplete="off"><input type="EMAIL" autocomplete="section-a shipping email"><button type="submit" dir="rtl">Send</button><a href="/" target="_blank" rel="noopener noreferrer"></a><link rel="stylesheet preload" crossorigin=""><input type="emial"></form>"#
+    );
+}
+
+#[test]
+fn t31() {
+    markup::define! {
+        #[markup(deny(a11y))]
+        Page<'a>(attributes: &'a [(&'a str, &'a str)]) {
+            html[lang = "en"] {
+                h1 { "Title" }
+                h2 { "Section" }
+                h3 { "Subsection" }
+                h2 { "Section" }
+                img[src = "/a.png", alt = ""];
+                img[..*attributes];
+                a[href = "/"] { "Home" }
+                form {
+                    label[for = "name"] { "Name" }
+                    input #name[type = "text"];
+                    label { "Message" textarea {} }
+                    select["aria-label" = "Color"] {}
+                    input[type = "hidden", name = "token"];
+                    button[type = "submit"] { "Send" }
+                }
+                #[markup(allow(missing_button_type))]
+                button { "Close" }
+            }
+        }
+    }
+
+    assert_eq!(
+        Page {
+            attributes: &[("src", "/b.png")]
+        }
+        .to_string(),
+        r#"<html lang="en"><h1>Title</h1><h2>Section</h2><h3>Subsection</h3><h2>Section</h2><img src="/a.png" alt=""><img src="/b.png"><a href="/">Home</a><form><label for="name">Name</label><input id="name" type="text"><label>Message<textarea></textarea></label><select aria-label="Color"></select><input type="hidden" name="token"><button type="submit">Send</button></form><button>Close</button></html>"#
+    );
+}
+
+#[test]
+fn t32() {
+    markup::define! {
+        Toggle<'a>(expanded: bool, pressed: Option<bool>, attributes: &'a [(&'a str, bool)]) {
+            button[
+                type = "button",
+                "aria-expanded" = expanded,
+                "aria-pressed" = pressed,
+                "data-open" = *expanded,
+                draggable = false,
+                spellcheck = true,
+                disabled = Some(false),
+                hidden = Some(true),
+                ..*attributes
+            ] {}
+        }
+    }
+
+    assert_eq!(
+        Toggle {
+            expanded: false,
+            pressed: None,
+            attributes: &[("aria-busy", false), ("inert", false), ("autofocus", true)],
+        }
+        .to_string(),
+        r#"<button type="button" aria-expanded="false" data-open="false" draggable="false" spellcheck="true" hidden aria-busy="false" autofocus></button>"#
+    );
+    assert_eq!(
+        Toggle {
+            expanded: true,
+            pressed: Some(false),
+            attributes: &[],
+        }
+        .to_string(),
+        r#"<button type="button" aria-expanded="true" aria-pressed="false" data-open="true" draggable="false" spellcheck="true" hidden></button>"#
+    );
+}
+
+#[test]
+fn t33() {
+    markup::define! {
+        #[markup(deny(id_references, duplicate_ids))]
+        Form(error: Option<&'static str>) {
+            a[href = "#email"] { "Skip to the form" }
+            a[href = "#"] { "Top" }
+            form {
+                label[for = "email"] { "Email" }
+                input #email[type = "email", "aria-describedby" = "hint error"];
+                p #hint { "We won't share it." }
+                @if let Some(error) = error {
+                    p #error { @error }
+                } else {
+                    p #error {}
+                }
+            }
+        }
+        #[markup(deny(id_references))]
+        Dynamic(id: &'static str) {
+            label[for = "name"] { "Name" }
+            input[id = id];
+        }
+    }
+
+    assert_eq!(
+        Form { error: None }.to_string(),
+        r##"<a href="#email">Skip to the form</a><a href="#">Top</a><form><label for="email">Email</label><input id="email" type="email" aria-describedby="hint error"><p id="hint">We won't share it.</p><p id="error"></p></form>"##
+    );
+    assert_eq!(
+        Dynamic { id: "name" }.to_string(),
+        r#"<label for="name">Name</label><input id="name">"#
+    );
+}
+
+#[test]
+fn t34() {
+    markup::define! {
+        #[markup(deny(attribute_names))]
+        Names(id: u32) {
+            my-element.btn-primary #main-content[data-id = id, data-2fa, aria-label = "Menu", hx-get = "/", x-on:click = "open = true", @click = "open = !open", :class = "{ open }", r#type = "button"] {}
+            svg[xmlns:xlink = "http://www.w3.org/1999/xlink"] { r#use[xlink:href = "#icon"] {} }
+        }
+    }
+
+    assert_eq!(
+        Names { id: 1 }.to_string(),
+        r##"<my-element id="main-content" class="btn-primary" data-id="1" data-2fa="true" aria-label="Menu" hx-get="/" x-on:click="open = true" @click="open = !open" :class="{ open }" type="button"></my-element><svg xmlns:xlink="http://www.w3.org/1999/xlink"><use xlink:href="#icon"></use></svg>"##
+    );
+}
+
+#[test]
+fn t35() {
+    enum State {
+        Open,
+        Closed(u32),
+    }
+
+    markup::define! {
+        Editor(editable: bool, state: State) {
+            div[class = "editor", @if *editable { contenteditable, tabindex = 0, data_state = "edit" } else { "aria-readonly" = true }] {}
+            details[
+                @match state {
+                    State::Open => { open, data-state = "open" }
+                    State::Closed(count) if *count > 0 => { data-count = count }
+                    State::Closed(_) => {}
+                },
+                id = "details",
+            ] {}
+        }
+    }
+
+    assert_eq!(
+        Editor {
+            editable: true,
+            state: State::Open
+        }
+        .to_string(),
+        r#"<div class="editor" contenteditable="true" tabindex="0" data_state="edit"></div><details open data-state="open" id="details"></details>"#
+    );
+    assert_eq!(
+        Editor {
+            editable: false,
+            state: State::Closed(2)
+        }
+        .to_string(),
+        r#"<div class="editor" aria-readonly="true"></div><details data-count="2" id="details"></details>"#
+    );
+    assert_eq!(
+        Editor {
+            editable: false,
+            state: State::Closed(0)
+        }
+        .to_string(),
+        r#"<div class="editor" aria-readonly="true"></div><details id="details"></details>"#
+    );
+}
+
+#[test]
+fn t36() {
+    markup::define! {
+        Prices<'a>(items: &'a [(&'a str, f64)], count: u32) {
+            @for (name, price) in *items {
+                @name:<8 "|" @price:>8.2 "|" @{price * 100.0:.0} "|" @{*price as u32:#06x} '\n'
+            }
+            @count:? " " @count:x .total { @{count:+} }
+            @{"<b>":?}
+        }
+    }
+
+    assert_eq!(
+        Prices {
+            items: &[("Tea", 3.5), ("Coffee", 12.25)],
+            count: 31,
+        }
+        .to_string(),
+        "Tea     |    3.50|350|0x0003\nCoffee  |   12.25|1225|0x000c\n31 1f<div class=\"total\">+31</div>&quot;&lt;b&gt;&quot;"
+    );
+}
+
+#[test]
+fn t37() {
+    markup::define! {
+        Inbox<'a>(name: &'a str, count: u32, total: f64) {
+            p { @$"Hello, {name}! You have {count} messages" }
+            p { @$"{{total}} = {total:.2} & {count:>3}" }
+            @$r#"<a title="{name}">"#
+        }
+    }
+
+    assert_eq!(
+        Inbox {
+            name: "<Ann>",
+            count: 3,
+            total: 1.5,
+        }
+        .to_string(),
+        "<p>Hello, &lt;Ann&gt;! You have 3 messages</p><p>{total} = 1.50 &amp;   3</p>&lt;a title=&quot;&lt;Ann&gt;&quot;&gt;"
+    );
+}
+
+#[test]
+fn t38() {
+    markup::define! {
+        Entities {
+            p { "Fish" &nbsp;&amp;&nbsp; "Chips" &mdash; &#163;3 &#x2014; &#X1F600; &in; }
+        }
+        #[markup(xml)]
+        XmlEntities {
+            title { "Fish" &nbsp;&amp;&nbsp; "Chips" &mdash; &#163; &lt; &NotNestedGreaterGreater; &in; }
+        }
+    }
+
+    assert_eq!(
+        Entities {}.to_string(),
+        "<p>Fish&nbsp;&amp;&nbsp;Chips&mdash;&#163;3&#x2014;&#X1F600;&in;</p>"
+    );
+    assert_eq!(
+        XmlEntities {}.to_string(),
+        "<title>Fish&#160;&amp;&#160;Chips&#8212;&#163;&lt;&#10914;&#824;&#8712;</title>"
+    );
+}
+
+#[test]
+fn t39() {
+    markup::define! {
+        Item<'a>(name: &'a str, link: Option<&'a str>, bold: bool) {
+            li {
+                @wrap_if let Some(link) = link a[href = link] {
+                    @wrap_if *bold strong.name { @name }
+                    " (" @link.is_some() ")"
+                }
+            }
+        }
+        Wrapper<'a>(tag: &'a str, wrap: bool) {
+            @wrap_if *wrap ${tag} { "Hi" }
+        }
+        Variable {
+            @let wrap_if = "<>";
+            @wrap_if " " @wrap_if.len()
+        }
+    }
+
+    assert_eq!(
+        Item {
+            name: "<Rust>",
+            link: Some("/rust"),
+            bold: true,
+        }
+        .to_string(),
+        r#"<li><a href="/rust"><strong class="name">&lt;Rust&gt;</strong> (true)</a></li>"#
+    );
+    assert_eq!(
+        Item {
+            name: "<Rust>",
+            link: None,
+            bold: false,
+        }
+        .to_string(),
+        "<li>&lt;Rust&gt; (false)</li>"
+    );
+    assert_eq!(
+        Wrapper {
+            tag: "em",
+            wrap: true
+        }
+        .to_string(),
+        "<em>Hi</em>"
+    );
+    assert_eq!(
+        Wrapper {
+            tag: "em",
+            wrap: false
+        }
+        .to_string(),
+        "Hi"
+    );
+    assert_eq!(Variable {}.to_string(), "&lt;&gt; 2");
+}
+
+#[test]
+fn t40() {
+    enum Status {
+        Ok,
+        Warning(u32),
+        Error(&'static str),
+    }
+
+    markup::define! {
+        Statuses(statuses: Vec<Status>) {
+            @for status in statuses {
+                @match status {
+                    Status::Ok => span.ok { "OK" }
+                    Status::Warning(count) if *count > 1 => @count,
+                    Status::Warning(_) | Status::Error("") => "?",
+                    Status::Error(message) => {
+                        strong { @message }
+                    }
+                }
+                div[@match status { Status::Ok => hidden, _ => class = "problem" }] {}
+            }
+        }
+    }
+
+    assert_eq!(
+        Statuses {
+            statuses: vec![
+                Status::Ok,
+                Status::Warning(2),
+                Status::Warning(1),
+                Status::Error("<!>")
+            ]
+        }
+        .to_string(),
+        concat!(
+            r#"<span class="ok">OK</span><div hidden></div>"#,
+            r#"2<div class="problem"></div>"#,
+            r#"?<div class="problem"></div>"#,
+            r#"<strong>&lt;!&gt;</strong><div class="problem"></div>"#,
+        )
+    );
+}
+
+#[test]
+fn t41() {
+    struct PageProps<'a> {
+        title: &'a str,
+        items: Vec<u32>,
+        footer: bool,
+    }
+
+    markup::define! {
+        Page<'a>(..props: PageProps<'a> { title, items }, active: u32) {
+            h1 { @title }
+            @for item in items {
+                @if item == active { b { @item } } else { @item }
+            }
+            @if props.footer { footer {} }
+        }
+        enum Section<'a> {
+            Main(..props: PageProps<'a> { title }) {
+                main { @title }
+            }
+        }
+    }
+
+    let props = PageProps {
+        title: "<Home>",
+        items: vec![1, 2, 3],
+        footer: true,
+    };
+    assert_eq!(
+        Section::Main { props }.to_string(),
+        "<main>&lt;Home&gt;</main>"
+    );
+    let props = PageProps {
+        title: "<Home>",
+        items: vec![1, 2, 3],
+        footer: true,
+    };
+    assert_eq!(
+        Page { props, active: 2 }.to_string(),
+        "<h1>&lt;Home&gt;</h1>1<b>2</b>3<footer></footer>"
+    );
+}
+
+#[test]
+fn t42() {
+    struct Dollars(u32);
+
+    impl std::fmt::Display for Dollars {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "${}", self.0)
+        }
+    }
+
+    markup::define! {
+        Layout<'a, H, B>(head: H, body: &'a B) {
+            head { @head }
+            body { @body }
+        }
+        // `price` is only rendered where it's shadowed, so `T` doesn't need to be `Render`.
+        Prices<T: std::fmt::Display>(price: T) {
+            @for price in [1, 2] { @price }
+            @if let Some(price) = Some(3) { @price }
+            @let price = price.to_string();
+            @price
+        }
+        enum Either<L, R> {
+            Left(value: L) { @value }
+            Right(value: R) { @$"[{value}]" }
+        }
+    }
+
+    assert_eq!(
+        Layout {
+            head: markup::new!(title { "Hi" }),
+            body: &"<p>"
+        }
+        .to_string(),
+        "<head><title>Hi</title></head><body>&lt;p&gt;</body>"
+    );
+    assert_eq!(Prices { price: Dollars(4) }.to_string(), "123$4");
+    assert_eq!(Either::<u8, &str>::Left { value: 1 }.to_string(), "1");
+    assert_eq!(Either::<u8, &str>::Right { value: "r" }.to_string(), "[r]");
+}
+
+#[test]
+fn t43() {
+    let currency = String::from("$");
+    let row = markup::new!(|n: u32, total: u32| {
+        @currency @n
+        @if *n > 3 { " big" }
+        @{ total + n }
+    });
+    assert_eq!(row(1, 10).to_string(), "$111");
+    assert_eq!(row(4, 10).to_string(), "$4 big14");
+    let template = row(5, 0);
+    assert_eq!(template.to_string(), template.to_string());
+}
+
+#[test]
+#[deny(deprecated)]
+fn t44() {
+    markup::define! {
+        Layout<Body: markup::Render>(body: Body) {
+            a[href = "#main"] { "Skip to content" }
+            @body
+        }
+        Content {
+            main #main { "Hi" }
+        }
+    }
+
+    assert_eq!(
+        Layout { body: Content {} }.to_string(),
+        r##"<a href="#main">Skip to content</a><main id="main">Hi</main>"##
+    );
+}
+
+#[test]
+#[deny(unused_variables)]
+fn t45() {
+    #[derive(markup::Render)]
+    #[markup {
+        h2 { @title }
+    }]
+    struct Article {
+        #[allow(dead_code)]
+        id: u64,
+        title: &'static str,
+    }
+
+    assert_eq!(Article { id: 1, title: "Hi" }.to_string(), "<h2>Hi</h2>");
+}
+
+#[test]
+fn t46() {
+    use std::cell::Cell;
+
+    fn count<'a>(calls: &Cell<u32>, name: &'a str) -> &'a str {
+        calls.set(calls.get() + 1);
+        name
+    }
+
+    markup::define! {
+        Names<'a>(calls: &'a Cell<u32>, name: &'a str, value: bool) {
+            div[{count(calls, name)} = *value] {}
+        }
+    }
+
+    let calls = Cell::new(0);
+    let render = |name, value| {
+        Names {
+            calls: &calls,
+            name,
+            value,
+        }
+        .to_string()
+    };
+    assert_eq!(
+        render("DATA-open", false),
+        r#"<div DATA-open="false"></div>"#
+    );
+    assert_eq!(
+        render("spellcheck", true),
+        r#"<div spellcheck="true"></div>"#
+    );
+    assert_eq!(render("hidden", true), r#"<div hidden></div>"#);
+    assert_eq!(render("hidden", false), r#"<div></div>"#);
+    assert_eq!(
+        render("contenteditable-x", true),
+        r#"<div contenteditable-x></div>"#
+    );
+    assert_eq!(calls.get(), 5);
+}
+
+#[test]
+fn t47() {
+    markup::define! {
+        Specs(n: u32, price: f64) {
+            @n:>4 p { "x" }
+            @price:.2 b { "y" }
+            @n:x br;
+            @n:<3 "|"
+            @n:03 span #total.n [title = "t"] {}
+        }
+    }
+
+    assert_eq!(
+        Specs { n: 12, price: 1.5 }.to_string(),
+        r#"  12<p>x</p>1.50<b>y</b>c<br>12 |012<span id="total" class="n" title="t"></span>"#
+    );
+}
+
+#[test]
+#[deny(unused_variables)]
+fn t48() {
+    mod props {
+        #[derive(markup::Props)]
+        pub struct PageProps<'a> {
+            pub title: &'a str,
+            pub items: &'a [u32],
+        }
+    }
+
+    #[derive(markup::Props)]
+    struct User {
+        name: String,
+        admin: bool,
+    }
+
+    markup::define! {
+        Page<'a>(..props: props::PageProps<'a>, ..user: &'a User) {
+            h1 { @title }
+            @for item in *items {
+                p { @item }
+            }
+            @if *admin { @name }
+        }
+        enum Card<'a> {
+            Title(..props: props::PageProps<'a>) { h2 { @title } }
+            Count(..props: props::PageProps<'a> { items }) { @items.len() }
+        }
+    }
+
+    let user = User {
+        name: "Ann".into(),
+        admin: true,
+    };
+    let props = || props::PageProps {
+        title: "Tea",
+        items: &[1, 2],
+    };
+    assert_eq!(
+        Page {
+            props: props(),
+            user: &user,
+        }
+        .to_string(),
+        "<h1>Tea</h1><p>1</p><p>2</p>Ann"
+    );
+    assert_eq!(Card::Title { props: props() }.to_string(), "<h2>Tea</h2>");
+    assert_eq!(Card::Count { props: props() }.to_string(), "2");
+}
+
+#[test]
+fn t49() {
+    use std::cell::Cell;
+
+    fn count<'a>(calls: &Cell<u32>, name: &'a str) -> &'a str {
+        calls.set(calls.get() + 1);
+        name
+    }
+
+    markup::define! {
+        Names<'a>(calls: &'a Cell<u32>, wrap: bool) {
+            ${count(calls, "section")} {
+                ${count(calls, "p")} { "x" }
+                @wrap_if *wrap ${count(calls, "em")} { "y" }
+            }
+        }
+    }
+
+    let calls = Cell::new(0);
+    assert_eq!(
+        Names {
+            calls: &calls,
+            wrap: true,
+        }
+        .to_string(),
+        "<section><p>x</p><em>y</em></section>"
+    );
+    assert_eq!(calls.get(), 3);
+    assert_eq!(
+        Names {
+            calls: &calls,
+            wrap: false,
+        }
+        .to_string(),
+        "<section><p>x</p>y</section>"
+    );
+    assert_eq!(calls.get(), 6);
+}
+
+t! {
+    t50,
+    {
+        Specs(n: u32) {
+            @n:4 b "bold"
+            @n:>8 p "x"
+            @n:4b "y"
+            @n:03 my-el "z"
+        }
+    },
+    Specs { n: 5 } => "   5<b>bold</b>       5<p>x</p> 101y005<my-el>z</my-el>",
+}
+
+#[test]
+fn t51() {
+    struct Name(&'static str);
+
+    let header = markup::new!(h1 { "x" });
+    let row = markup::new!(|n: u32, name: Name| {
+        @header @n @name.0
+    });
+    assert_eq!(row(1, Name("a")).to_string(), "<h1>x</h1>1a");
+    let template = row(2, Name("b"));
+    assert_eq!(template.to_string(), "<h1>x</h1>2b");
+    assert_eq!(template.to_string(), "<h1>x</h1>2b");
+}
+
+#[test]
+fn t52() {
+    mod models {
+        #[derive(markup::Props)]
+        pub struct PageProps {
+            pub title: String,
+        }
+
+        pub mod reexport {
+            pub use super::PageProps;
+        }
+    }
+
+    use models::PageProps;
+
+    markup::define! {
+        Page(..props: PageProps) {
+            h1 { @title }
+        }
+        Other(..props: models::reexport::PageProps) {
+            h2 { @title }
+        }
+    }
+
+    let props = || PageProps {
+        title: "Tea".into(),
+    };
+    assert_eq!(Page { props: props() }.to_string(), "<h1>Tea</h1>");
+    assert_eq!(Other { props: props() }.to_string(), "<h2>Tea</h2>");
+}
+
+#[test]
+fn t53() {
+    struct Item {
+        name: String,
+    }
+
+    let currency = String::from("$");
+    let items = ["a", "<b>"]
+        .into_iter()
+        .map(|name| Item { name: name.into() })
+        .collect::<Vec<_>>();
+    let row = markup::new!(|item: &Item, suffix: &str| {
+        li { @item.name @currency @suffix }
+    });
+    let list = markup::new! {
+        ul {
+            @for item in items.iter() {
+                @row(item, &item.name.len().to_string())
+            }
+        }
+    };
+    assert_eq!(
+        list.to_string(),
+        "<ul><li>a$1</li><li>&lt;b&gt;$3</li></ul>"
+    );
+}
+
+t! {
+    t54,
+    {
+        Fill(n: u32) {
+            @n:*>6 "|" @n:_<4 "|" @{n:0^5} "|" @$"{n:-^7}"
+        }
+    },
+    Fill { n: 42 } => "****42|42__|04200|--42---",
+}
@@ -0,0 +1,24 @@
+markup::define! {
+    #[markup(warn(a11y))]
+    Warned {
+        html {
+            h1 { "Title" }
+            h3 { "Subsection" }
+        }
+    }
+}
+
+markup::define! {
+    #[markup(deny(a11y), allow(missing_lang))]
+    Denied {
+        img[src = "/a.png"];
+        a { "Home" }
+        form {
+            input #name[type = "email"];
+            textarea {}
+            button { "Send" }
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+markup::define! {
+    #[markup(warn(content_model))]
+    Warned {
+        p { div {} }
+        span { a { ul {} } }
+        ul { p {} }
+        div { li {} }
+    }
+    #[markup(deny(content_model))]
+    Denied {
+        a { p { button {} } }
+        form { div { form {} } }
+        #[markup(warn(content_model))]
+        table { td {} }
+    }
+}
+
+markup::define! {
+    #[markup(warn(content_modle))]
+    Unknown {}
+}
+
+fn main() {}
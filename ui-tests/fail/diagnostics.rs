@@ -0,0 +1,40 @@
+markup::define! {
+    Reserved {
+        div#foo.bar { "x" }
+    }
+}
+
+markup::define! {
+    Parse(name: &'static str) {
+        p { name }
+        p { self.name }
+        span.a { foo(1) }
+    }
+    Next {
+        div[x = ] {}
+    }
+    WrapIf(wrap: bool) {
+        @wrap_if *wrap hr;
+    }
+    WrapIfClass(wrap: bool) {
+        @wrap_if *wrap .card { "x" }
+    }
+}
+
+markup::define! {
+    MatchComma(x: (u32, u32)) {
+        @match x {
+            (0, _) => @x.0
+            (_, 0) => "b",
+            _ => "c"
+        }
+    }
+    MatchNodes(x: u32) {
+        @match x {
+            0 => "a" "b",
+            _ => "c"
+        }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+markup::define! {
+    Entities {
+        p { &nbps; }
+        p { &NBSP; }
+        p { &amo; }
+        p { &qwertyuiop; }
+        p { &nbsp }
+        p { &#0; }
+        p { &#xZZ; }
+        p { &#1114112; }
+    }
+}
+
+fn main() {}
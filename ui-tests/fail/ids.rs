@@ -0,0 +1,22 @@
+markup::define! {
+    #[markup(warn(id_references))]
+    Ids {
+        label[for = "emial"] { "Email" }
+        input #email[type = "email", "aria-describedby" = "hint"];
+        a[href = "#main"] {}
+        div #email {}
+        #[markup(deny(duplicate_ids))]
+        div[id = "email"] {}
+    }
+}
+
+markup::define! {
+    #[markup(deny(id_references))]
+    Rendered(error: &'static str) {
+        label[for = "emial"] {}
+        input #email;
+        p { @error }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+markup::define! {
+    Syntax() include!("../../../../ui-tests/fail/include/syntax.markup")
+}
+
+fn main() {
+    let _ = markup::include!("../../../../ui-tests/fail/include/unclosed.markup");
+}
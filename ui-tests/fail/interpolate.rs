@@ -0,0 +1,11 @@
+markup::define! {
+    Interpolate(name: &'static str, count: u32) {
+        p { @$"Hello, {name" }
+        p { @$"Hello, }{name}" }
+        p { @$"{name.len()}" }
+        p { @$"{}" }
+        p { @$"{count:.x}" }
+    }
+}
+
+fn main() {}
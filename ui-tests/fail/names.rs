@@ -0,0 +1,11 @@
+markup::define! {
+    #[markup(deny(attribute_names, element_names))]
+    Names {
+        inptu[tyep = "text"];
+        a[hreff = "/"] {}
+        div[foo = "bar"] {}
+        custom {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+struct PageProps {
+    title: &'static str,
+}
+
+markup::define! {
+    Page(..props: PageProps) {
+        h1 { @title }
+    }
+}
+
+markup::define! {
+    Other(..props: PageProps { title, items }) {
+        h1 { @title }
+    }
+}
+
+markup::define! {
+    Tuple(..props: (u32, u32) { first }) {
+        @first
+    }
+}
+
+#[derive(markup::Props)]
+enum Kind {
+    A,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+markup::define! {
+    Warned {
+        input[type = "emial"];
+        button[type = "sumbit"] {}
+    }
+}
+
+markup::define! {
+    #[markup(deny(attribute_values))]
+    Denied {
+        form[method = "put", target = "_new"] {}
+        a[rel = "noopener norefferer"] {}
+        input[autocomplete = "nope"];
+    }
+}
+
+fn main() {}
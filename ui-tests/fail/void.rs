@@ -0,0 +1,11 @@
+markup::define! {
+    Void {
+        div;
+        br { "x" }
+        img {}
+        input;
+        $"my-element";
+    }
+}
+
+fn main() {}
@@ -4,6 +4,7 @@ fn ui() {
     assert_eq!(version.major, 1);
 
     let fail = trybuild::TestCases::new();
+    fail.compile_fail("fail/*.rs");
     if version.minor <= 65 {
         fail.compile_fail("fail-1.65/*.rs");
     } else {